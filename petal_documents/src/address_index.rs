@@ -0,0 +1,105 @@
+use soroban_sdk::{Address, Env};
+
+use crate::storage_types::{AddressIndexKey, DataKey, SignatureDataKey};
+use crate::ttl::bump_persistent;
+
+// Each address's documents are kept like the global token enumeration: a
+// count plus one entry per position, so no single entry grows with the number
+// of documents. Removed signers leave a gap at their position, which keeps
+// cursors into the list stable.
+
+pub fn read_owner_document_count(e: &Env, owner: Address) -> u32 {
+    let key = DataKey::OwnerDocumentCount(owner);
+    e.storage().persistent().get(&key).unwrap_or(0)
+}
+
+pub fn read_owner_document(e: &Env, owner: Address, index: u32) -> Option<u32> {
+    let key = DataKey::OwnerDocument(AddressIndexKey {
+        address: owner,
+        index,
+    });
+    e.storage().persistent().get(&key)
+}
+
+pub fn read_owner_document_index(e: &Env, token_id: u32) -> Option<u32> {
+    let key = DataKey::OwnerDocumentIndex(token_id);
+    e.storage().persistent().get(&key)
+}
+
+pub fn index_owner(e: &Env, owner: Address, token_id: u32) {
+    if read_owner_document_index(e, token_id).is_some() {
+        return;
+    }
+    let count = read_owner_document_count(e, owner.clone());
+    let key = DataKey::OwnerDocument(AddressIndexKey {
+        address: owner.clone(),
+        index: count,
+    });
+    e.storage().persistent().set(&key, &token_id);
+    bump_persistent(e, &key);
+    let key = DataKey::OwnerDocumentIndex(token_id);
+    e.storage().persistent().set(&key, &count);
+    bump_persistent(e, &key);
+    let key = DataKey::OwnerDocumentCount(owner);
+    e.storage().persistent().set(&key, &(count + 1));
+    bump_persistent(e, &key);
+}
+
+pub fn read_signer_document_count(e: &Env, signer: Address) -> u32 {
+    let key = DataKey::SignerDocumentCount(signer);
+    e.storage().persistent().get(&key).unwrap_or(0)
+}
+
+pub fn read_signer_document(e: &Env, signer: Address, index: u32) -> Option<u32> {
+    let key = DataKey::SignerDocument(AddressIndexKey {
+        address: signer,
+        index,
+    });
+    e.storage().persistent().get(&key)
+}
+
+pub fn read_signer_document_index(e: &Env, signer: Address, token_id: u32) -> Option<u32> {
+    let key = DataKey::SignerDocumentIndex(SignatureDataKey { token_id, signer });
+    e.storage().persistent().get(&key)
+}
+
+pub fn index_signer(e: &Env, signer: Address, token_id: u32) {
+    if read_signer_document_index(e, signer.clone(), token_id).is_some() {
+        return;
+    }
+    let count = read_signer_document_count(e, signer.clone());
+    let key = DataKey::SignerDocument(AddressIndexKey {
+        address: signer.clone(),
+        index: count,
+    });
+    e.storage().persistent().set(&key, &token_id);
+    bump_persistent(e, &key);
+    let key = DataKey::SignerDocumentIndex(SignatureDataKey {
+        token_id,
+        signer: signer.clone(),
+    });
+    e.storage().persistent().set(&key, &count);
+    bump_persistent(e, &key);
+    let key = DataKey::SignerDocumentCount(signer);
+    e.storage().persistent().set(&key, &(count + 1));
+    bump_persistent(e, &key);
+}
+
+pub fn unindex_signer(e: &Env, signer: Address, token_id: u32) {
+    let index = match read_signer_document_index(e, signer.clone(), token_id) {
+        Some(index) => index,
+        None => return,
+    };
+    e.storage()
+        .persistent()
+        .remove(&DataKey::SignerDocument(AddressIndexKey {
+            address: signer.clone(),
+            index,
+        }));
+    e.storage()
+        .persistent()
+        .remove(&DataKey::SignerDocumentIndex(SignatureDataKey {
+            token_id,
+            signer,
+        }));
+}
@@ -0,0 +1,69 @@
+use soroban_sdk::{Address, Env, Vec};
+
+use crate::storage_types::{DataKey, Delegation, DelegationDataKey};
+use crate::ttl::bump_persistent;
+use crate::DelegationScope;
+
+pub fn read_delegation(e: &Env, delegator: Address, delegate: Address) -> Option<Delegation> {
+    let key = DataKey::Delegation(DelegationDataKey {
+        delegator,
+        delegate,
+    });
+    e.storage().persistent().get(&key)
+}
+
+pub fn read_delegates(e: &Env, delegator: Address) -> Vec<Address> {
+    let key = DataKey::Delegates(delegator);
+    e.storage().persistent().get(&key).unwrap_or(Vec::new(e))
+}
+
+pub fn write_delegation(e: &Env, delegator: Address, delegation: &Delegation) {
+    let mut delegates = read_delegates(e, delegator.clone());
+    if !delegates.contains(&delegation.delegate) {
+        delegates.push_back(delegation.delegate.clone());
+        e.storage()
+            .persistent()
+            .set(&DataKey::Delegates(delegator.clone()), &delegates);
+    }
+    bump_persistent(e, &DataKey::Delegates(delegator.clone()));
+
+    let key = DataKey::Delegation(DelegationDataKey {
+        delegator,
+        delegate: delegation.delegate.clone(),
+    });
+    e.storage().persistent().set(&key, delegation);
+    bump_persistent(e, &key);
+}
+
+pub fn remove_delegation(e: &Env, delegator: Address, delegate: Address) -> bool {
+    let mut delegates = read_delegates(e, delegator.clone());
+    let index = match delegates.first_index_of(&delegate) {
+        Some(index) => index,
+        None => return false,
+    };
+    delegates.remove(index);
+    e.storage()
+        .persistent()
+        .set(&DataKey::Delegates(delegator.clone()), &delegates);
+
+    let key = DataKey::Delegation(DelegationDataKey {
+        delegator,
+        delegate,
+    });
+    e.storage().persistent().remove(&key);
+    true
+}
+
+/// Whether `delegate` may currently act for `delegator` on `token_id`.
+pub fn is_delegate_for(e: &Env, delegator: Address, delegate: Address, token_id: u32) -> bool {
+    match read_delegation(e, delegator, delegate) {
+        Some(delegation) => {
+            e.ledger().timestamp() <= delegation.expires_at
+                && match delegation.scope {
+                    DelegationScope::AllDocuments => true,
+                    DelegationScope::Document(scope) => scope == token_id,
+                }
+        }
+        None => false,
+    }
+}
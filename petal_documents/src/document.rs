@@ -0,0 +1,355 @@
+use soroban_sdk::{Address, Env, Map, Vec};
+
+use crate::storage_types::{
+    DataKey, DocumentClosure, DocumentRecord, DocumentVersion, Rejection, SignatureDataKey,
+    SignatureReceipt, SignatureRevocation, SignerEntry, SignerReplacement, VersionDataKey,
+};
+use crate::ttl::bump_persistent;
+use crate::{ActedBy, DocumentState, RejectionPolicy, SignatureStatus, SignerRole};
+
+pub fn has_document(e: &Env, token_id: u32) -> bool {
+    let key = DataKey::Document(token_id);
+    e.storage().persistent().has(&key)
+}
+
+pub fn read_document(e: &Env, token_id: u32) -> Option<DocumentRecord> {
+    let key = DataKey::Document(token_id);
+    e.storage().persistent().get(&key)
+}
+
+pub fn write_document(e: &Env, token_id: u32, document: &DocumentRecord) {
+    let key = DataKey::Document(token_id);
+    e.storage().persistent().set(&key, document);
+    bump_persistent(e, &key);
+}
+
+pub fn read_signers(e: &Env, token_id: u32) -> Vec<Address> {
+    let key = DataKey::Signers(token_id);
+    e.storage().persistent().get(&key).unwrap_or(Vec::new(e))
+}
+
+pub fn write_signers(e: &Env, token_id: u32, signers: &Vec<Address>) {
+    let key = DataKey::Signers(token_id);
+    e.storage().persistent().set(&key, signers);
+    bump_persistent(e, &key);
+}
+
+pub fn read_signer_entry(e: &Env, token_id: u32, signer: Address) -> Option<SignerEntry> {
+    let key = DataKey::Signature(SignatureDataKey { token_id, signer });
+    e.storage().persistent().get(&key)
+}
+
+pub fn write_signer_entry(e: &Env, token_id: u32, signer: Address, entry: &SignerEntry) {
+    let key = DataKey::Signature(SignatureDataKey { token_id, signer });
+    e.storage().persistent().set(&key, entry);
+    bump_persistent(e, &key);
+}
+
+/// Adds a participant in its initial status: viewers can never sign, every
+/// other role starts out waiting.
+pub fn add_signer_entry(e: &Env, token_id: u32, signer: Address, role: SignerRole) {
+    let status = if role == SignerRole::Viewer {
+        SignatureStatus::NotASigner
+    } else {
+        SignatureStatus::Waiting
+    };
+    let entry = SignerEntry {
+        status,
+        role,
+        acted_by: ActedBy::Signer,
+    };
+    write_signer_entry(e, token_id, signer, &entry);
+}
+
+pub fn read_signature(e: &Env, token_id: u32, signer: Address) -> Option<SignatureStatus> {
+    read_signer_entry(e, token_id, signer).map(|entry| entry.status)
+}
+
+pub fn write_signature(
+    e: &Env,
+    token_id: u32,
+    signer: Address,
+    status: &SignatureStatus,
+    acted_by: ActedBy,
+) {
+    let role = match read_signer_entry(e, token_id, signer.clone()) {
+        Some(entry) => entry.role,
+        None => SignerRole::Signer,
+    };
+    let entry = SignerEntry {
+        status: status.clone(),
+        role,
+        acted_by,
+    };
+    write_signer_entry(e, token_id, signer, &entry);
+}
+
+pub fn remove_signature(e: &Env, token_id: u32, signer: Address) {
+    let key = DataKey::Signature(SignatureDataKey { token_id, signer });
+    e.storage().persistent().remove(&key);
+}
+
+pub fn read_receipt(e: &Env, token_id: u32, signer: Address) -> Option<SignatureReceipt> {
+    let key = DataKey::Receipt(SignatureDataKey { token_id, signer });
+    e.storage().persistent().get(&key)
+}
+
+pub fn write_receipt(e: &Env, token_id: u32, signer: Address, receipt: &SignatureReceipt) {
+    let key = DataKey::Receipt(SignatureDataKey { token_id, signer });
+    e.storage().persistent().set(&key, receipt);
+    bump_persistent(e, &key);
+}
+
+pub fn remove_receipt(e: &Env, token_id: u32, signer: Address) {
+    let key = DataKey::Receipt(SignatureDataKey { token_id, signer });
+    e.storage().persistent().remove(&key);
+}
+
+pub fn read_revocations(e: &Env, token_id: u32, signer: Address) -> Vec<SignatureRevocation> {
+    let key = DataKey::Revocations(SignatureDataKey { token_id, signer });
+    e.storage().persistent().get(&key).unwrap_or(Vec::new(e))
+}
+
+pub fn write_revocations(
+    e: &Env,
+    token_id: u32,
+    signer: Address,
+    revocations: &Vec<SignatureRevocation>,
+) {
+    let key = DataKey::Revocations(SignatureDataKey { token_id, signer });
+    e.storage().persistent().set(&key, revocations);
+    bump_persistent(e, &key);
+}
+
+pub fn read_rejection(e: &Env, token_id: u32, signer: Address) -> Option<Rejection> {
+    let key = DataKey::Rejection(SignatureDataKey { token_id, signer });
+    e.storage().persistent().get(&key)
+}
+
+pub fn write_rejection(e: &Env, token_id: u32, signer: Address, rejection: &Rejection) {
+    let key = DataKey::Rejection(SignatureDataKey { token_id, signer });
+    e.storage().persistent().set(&key, rejection);
+    bump_persistent(e, &key);
+}
+
+pub fn remove_rejection(e: &Env, token_id: u32, signer: Address) {
+    let key = DataKey::Rejection(SignatureDataKey { token_id, signer });
+    e.storage().persistent().remove(&key);
+}
+
+pub fn read_receipts(e: &Env, token_id: u32) -> Map<Address, SignatureReceipt> {
+    let mut receipts = Map::new(e);
+    for signer in read_signers(e, token_id).iter() {
+        if let Some(receipt) = read_receipt(e, token_id, signer.clone()) {
+            receipts.set(signer, receipt);
+        }
+    }
+    receipts
+}
+
+pub fn read_rejections(e: &Env, token_id: u32) -> Map<Address, Rejection> {
+    let mut rejections = Map::new(e);
+    for signer in read_signers(e, token_id).iter() {
+        if let Some(rejection) = read_rejection(e, token_id, signer.clone()) {
+            rejections.set(signer, rejection);
+        }
+    }
+    rejections
+}
+
+pub fn read_replacements(e: &Env, token_id: u32) -> Vec<SignerReplacement> {
+    let key = DataKey::Replacements(token_id);
+    e.storage().persistent().get(&key).unwrap_or(Vec::new(e))
+}
+
+pub fn write_replacements(e: &Env, token_id: u32, replacements: &Vec<SignerReplacement>) {
+    let key = DataKey::Replacements(token_id);
+    e.storage().persistent().set(&key, replacements);
+    bump_persistent(e, &key);
+}
+
+pub fn next_waiting_signer(e: &Env, token_id: u32) -> Option<Address> {
+    read_signers(e, token_id).iter().find(|signer| {
+        read_signature(e, token_id, signer.clone()) == Some(SignatureStatus::Waiting)
+    })
+}
+
+pub fn read_closure(e: &Env, token_id: u32) -> Option<DocumentClosure> {
+    let key = DataKey::Closure(token_id);
+    e.storage().persistent().get(&key)
+}
+
+pub fn write_closure(e: &Env, token_id: u32, closure: &DocumentClosure) {
+    let key = DataKey::Closure(token_id);
+    e.storage().persistent().set(&key, closure);
+    bump_persistent(e, &key);
+}
+
+pub fn read_signer_entries(e: &Env, token_id: u32) -> Map<Address, SignerEntry> {
+    let mut entries = Map::new(e);
+    for signer in read_signers(e, token_id).iter() {
+        if let Some(entry) = read_signer_entry(e, token_id, signer.clone()) {
+            entries.set(signer, entry);
+        }
+    }
+    entries
+}
+
+pub fn read_signings(e: &Env, token_id: u32) -> Map<Address, SignatureStatus> {
+    let mut signings = Map::new(e);
+    for (signer, entry) in read_signer_entries(e, token_id).iter() {
+        signings.set(signer, entry.status);
+    }
+    signings
+}
+
+/// Every participant except viewers has to take part in completion.
+pub fn count_required(entries: &Map<Address, SignerEntry>) -> u32 {
+    entries
+        .values()
+        .iter()
+        .filter(|entry| entry.role != SignerRole::Viewer)
+        .count() as u32
+}
+
+/// Number of signatures needed to complete a document. A threshold of zero
+/// requires every signer.
+pub fn required_signatures(threshold: u32, signer_count: u32) -> u32 {
+    if threshold == 0 {
+        signer_count
+    } else {
+        threshold
+    }
+}
+
+pub fn count_signed(entries: &Map<Address, SignerEntry>) -> u32 {
+    entries
+        .values()
+        .iter()
+        .filter(|entry| entry.status == SignatureStatus::Signed)
+        .count() as u32
+}
+
+pub fn count_rejected(entries: &Map<Address, SignerEntry>) -> u32 {
+    entries
+        .values()
+        .iter()
+        .filter(|entry| entry.status == SignatureStatus::Rejected)
+        .count() as u32
+}
+
+/// Derives the state a document reaches from its signatures alone. It
+/// completes once the signing threshold over the required roles is met. Under
+/// `Terminate` a single rejection rejects it; under `Continue` it is only
+/// rejected once the threshold can no longer be reached.
+pub fn resolve_state(
+    e: &Env,
+    token_id: u32,
+    threshold: u32,
+    rejection_policy: RejectionPolicy,
+) -> DocumentState {
+    let entries = read_signer_entries(e, token_id);
+    let required = count_required(&entries);
+    let rejected = count_rejected(&entries);
+    let terminated = match rejection_policy {
+        RejectionPolicy::Terminate => rejected > 0,
+        RejectionPolicy::Continue => required - rejected < required_signatures(threshold, required),
+    };
+    if terminated {
+        DocumentState::Rejected
+    } else if required > 0 && count_signed(&entries) >= required_signatures(threshold, required) {
+        DocumentState::Completed
+    } else {
+        DocumentState::Open
+    }
+}
+
+/// The stored state, reporting open documents past their deadline as expired.
+pub fn current_state(e: &Env, document: &DocumentRecord) -> DocumentState {
+    if document.state == DocumentState::Open && e.ledger().timestamp() > document.deadline {
+        DocumentState::Expired
+    } else {
+        document.state
+    }
+}
+
+pub fn read_version(e: &Env, token_id: u32, version: u32) -> Option<DocumentVersion> {
+    let key = DataKey::Version(VersionDataKey { token_id, version });
+    e.storage().persistent().get(&key)
+}
+
+pub fn write_version(e: &Env, token_id: u32, version: &DocumentVersion) {
+    let key = DataKey::Version(VersionDataKey {
+        token_id,
+        version: version.version,
+    });
+    e.storage().persistent().set(&key, version);
+    bump_persistent(e, &key);
+}
+
+/// Captures the current version of a document together with the signatures,
+/// receipts and rejections collected for it.
+pub fn snapshot_version(e: &Env, token_id: u32, document: &DocumentRecord) -> DocumentVersion {
+    DocumentVersion {
+        version: document.version,
+        uri: document.uri.clone(),
+        document_hash: document.document_hash.clone(),
+        previous_hash: document.previous_hash.clone(),
+        hash_algorithm: document.hash_algorithm,
+        file_count: document.file_count,
+        state: current_state(e, document),
+        signatures: read_signer_entries(e, token_id),
+        receipts: read_receipts(e, token_id),
+        rejections: read_rejections(e, token_id),
+    }
+}
+
+pub fn read_token_count(e: &Env) -> u32 {
+    let key = DataKey::TokenCount;
+    e.storage().persistent().get(&key).unwrap_or(0)
+}
+
+pub fn read_token_by_index(e: &Env, index: u32) -> Option<u32> {
+    let key = DataKey::TokenByIndex(index);
+    e.storage().persistent().get(&key)
+}
+
+pub fn read_token_index(e: &Env, token_id: u32) -> Option<u32> {
+    let key = DataKey::TokenIndex(token_id);
+    e.storage().persistent().get(&key)
+}
+
+pub fn read_last_token_id(e: &Env) -> u32 {
+    let key = DataKey::LastTokenId;
+    e.storage().instance().get(&key).unwrap_or(0)
+}
+
+pub fn write_last_token_id(e: &Env, token_id: u32) {
+    let key = DataKey::LastTokenId;
+    e.storage().instance().set(&key, &token_id);
+}
+
+/// Assigns the id following the highest one minted so far.
+pub fn next_token_id(e: &Env) -> u32 {
+    let token_id = read_last_token_id(e) + 1;
+    write_last_token_id(e, token_id);
+    token_id
+}
+
+/// Records a minted token in the enumeration index. Explicitly supplied ids
+/// above the counter move it forward so they are never assigned again.
+pub fn append_token(e: &Env, token_id: u32) {
+    if token_id > read_last_token_id(e) {
+        write_last_token_id(e, token_id);
+    }
+    let count = read_token_count(e);
+    let key = DataKey::TokenByIndex(count);
+    e.storage().persistent().set(&key, &token_id);
+    bump_persistent(e, &key);
+    let key = DataKey::TokenIndex(token_id);
+    e.storage().persistent().set(&key, &count);
+    bump_persistent(e, &key);
+    let key = DataKey::TokenCount;
+    e.storage().persistent().set(&key, &(count + 1));
+    bump_persistent(e, &key);
+}
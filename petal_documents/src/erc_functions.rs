@@ -1,17 +1,13 @@
-use soroban_sdk::{Address, Env, Map, log};
+use soroban_sdk::{Address, Env};
 
-pub fn owner_of(e: &Env, token_id: u32, owners: &Map<u32, Address>) -> Address {
-    owners.get(token_id).expect("Address does not exist for given token id").clone()
+use crate::document::{has_document, read_document};
+
+pub fn owner_of(e: &Env, token_id: u32) -> Address {
+    read_document(e, token_id)
+        .expect("Address does not exist for given token id")
+        .owner
 }
 
-pub fn exists(e: &Env, token_id: u32, owners: &Map<u32, Address>) -> bool {
-    let address = owners.get(token_id);
-    match address {
-        Some(v) => {
-            true
-        },
-        None => {
-            false
-        }
-    }
-}
\ No newline at end of file
+pub fn exists(e: &Env, token_id: u32) -> bool {
+    has_document(e, token_id)
+}
@@ -1,36 +1,362 @@
-use soroban_sdk::{symbol_short, Address, Env, Symbol, U256};
+use soroban_sdk::{contracttype, symbol_short, Address, BytesN, Env, String, Symbol};
 
-pub(crate) fn approve(e: &Env, from: Address, to: Address, amount: i128, expiration_ledger: u32) {
-    let topics = (Symbol::new(e, "approve"), from, to);
-    e.events().publish(topics, (amount, expiration_ledger));
+use crate::{ActedBy, DelegationScope, HashAlgorithm, RejectionPolicy, SignerRole, SigningOrder};
+
+// Document events are published under `(name, token_id)` topics so an indexer
+// can rebuild a document's history by filtering on its id.
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[contracttype]
+pub struct DocumentCreated {
+    pub owner: Address,
+    pub uri: String,
+    pub document_hash: BytesN<32>,
+    pub deadline: u64,
+    pub signing_order: SigningOrder,
+    pub threshold: u32,
+    pub rejection_policy: RejectionPolicy,
+    pub hash_algorithm: HashAlgorithm,
+    pub file_count: u32,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[contracttype]
+pub struct SignerAdded {
+    pub signer: Address,
+    pub role: SignerRole,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[contracttype]
+pub struct SignerRemoved {
+    pub signer: Address,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[contracttype]
+pub struct SignerReplaced {
+    pub previous: Address,
+    pub replacement: Address,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[contracttype]
+pub struct Signed {
+    pub signer: Address,
+    pub acted_by: ActedBy,
+    pub submitter: Address,
+    pub nonce: u32,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[contracttype]
+pub enum RejectionReason {
+    Unspecified,
+    Hash(BytesN<32>),
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[contracttype]
+pub struct Rejected {
+    pub signer: Address,
+    pub acted_by: ActedBy,
+    pub submitter: Address,
+    pub nonce: u32,
+    pub reason: RejectionReason,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[contracttype]
+pub struct SignatureRevoked {
+    pub signer: Address,
+    pub nonce: u32,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[contracttype]
+pub struct Completed {
+    pub signed: u32,
+    pub threshold: u32,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[contracttype]
+pub struct DocumentRejected {
+    pub rejected: u32,
+}
+
+/// Published when `expire_document` records an expiry. A document can be past
+/// its deadline well before that; indexers should compare the deadline with
+/// the ledger time rather than wait for this event.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[contracttype]
+pub struct Expired {
+    pub deadline: u64,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[contracttype]
+pub struct Cancelled {
+    pub owner: Address,
+    pub reason_hash: BytesN<32>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[contracttype]
+pub struct Voided {
+    pub voided_by: Address,
+    pub reason_hash: BytesN<32>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[contracttype]
+pub struct Amended {
+    pub version: u32,
+    pub document_hash: BytesN<32>,
+    pub previous_hash: BytesN<32>,
+    pub uri: String,
+    pub hash_algorithm: HashAlgorithm,
+    pub file_count: u32,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[contracttype]
+pub struct FeeCollected {
+    pub from: Address,
+    pub token: Address,
+    pub amount: i128,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[contracttype]
+pub struct CreationFeeSet {
+    pub token: Address,
+    pub amount: i128,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[contracttype]
+pub struct UniqueHashesSet {
+    pub enabled: bool,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[contracttype]
+pub struct FeesWithdrawn {
+    pub treasury: Address,
+    pub token: Address,
+    pub amount: i128,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[contracttype]
+pub struct DelegationAdded {
+    pub delegate: Address,
+    pub scope: DelegationScope,
+    pub expires_at: u64,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[contracttype]
+pub struct DelegationRevoked {
+    pub delegate: Address,
+}
+
+pub(crate) fn document_created(
+    e: &Env,
+    token_id: u32,
+    owner: Address,
+    uri: String,
+    document_hash: BytesN<32>,
+    deadline: u64,
+    signing_order: SigningOrder,
+    threshold: u32,
+    rejection_policy: RejectionPolicy,
+    hash_algorithm: HashAlgorithm,
+    file_count: u32,
+) {
+    let topics = (Symbol::new(e, "document_created"), token_id);
+    let event = DocumentCreated {
+        owner,
+        uri,
+        document_hash,
+        deadline,
+        signing_order,
+        threshold,
+        rejection_policy,
+        hash_algorithm,
+        file_count,
+    };
+    e.events().publish(topics, event);
+}
+
+pub(crate) fn signer_added(e: &Env, token_id: u32, signer: Address, role: SignerRole) {
+    let topics = (Symbol::new(e, "signer_added"), token_id);
+    e.events().publish(topics, SignerAdded { signer, role });
+}
+
+pub(crate) fn signer_removed(e: &Env, token_id: u32, signer: Address) {
+    let topics = (Symbol::new(e, "signer_removed"), token_id);
+    e.events().publish(topics, SignerRemoved { signer });
+}
+
+pub(crate) fn signer_replaced(e: &Env, token_id: u32, previous: Address, replacement: Address) {
+    let topics = (Symbol::new(e, "signer_replaced"), token_id);
+    let event = SignerReplaced {
+        previous,
+        replacement,
+    };
+    e.events().publish(topics, event);
+}
+
+pub(crate) fn signed(
+    e: &Env,
+    token_id: u32,
+    signer: Address,
+    acted_by: ActedBy,
+    submitter: Address,
+    nonce: u32,
+) {
+    let topics = (symbol_short!("signed"), token_id);
+    let event = Signed {
+        signer,
+        acted_by,
+        submitter,
+        nonce,
+    };
+    e.events().publish(topics, event);
+}
+
+pub(crate) fn rejected(
+    e: &Env,
+    token_id: u32,
+    signer: Address,
+    acted_by: ActedBy,
+    submitter: Address,
+    nonce: u32,
+    reason: RejectionReason,
+) {
+    let topics = (symbol_short!("rejected"), token_id);
+    let event = Rejected {
+        signer,
+        acted_by,
+        submitter,
+        nonce,
+        reason,
+    };
+    e.events().publish(topics, event);
+}
+
+pub(crate) fn signature_revoked(e: &Env, token_id: u32, signer: Address, nonce: u32) {
+    let topics = (Symbol::new(e, "signature_revoked"), token_id);
+    e.events()
+        .publish(topics, SignatureRevoked { signer, nonce });
+}
+
+pub(crate) fn completed(e: &Env, token_id: u32, signed: u32, threshold: u32) {
+    let topics = (symbol_short!("completed"), token_id);
+    e.events().publish(topics, Completed { signed, threshold });
+}
+
+pub(crate) fn document_rejected(e: &Env, token_id: u32, rejected: u32) {
+    let topics = (Symbol::new(e, "document_rejected"), token_id);
+    e.events().publish(topics, DocumentRejected { rejected });
+}
+
+pub(crate) fn expired(e: &Env, token_id: u32, deadline: u64) {
+    let topics = (symbol_short!("expired"), token_id);
+    e.events().publish(topics, Expired { deadline });
+}
+
+pub(crate) fn cancelled(e: &Env, token_id: u32, owner: Address, reason_hash: BytesN<32>) {
+    let topics = (symbol_short!("cancelled"), token_id);
+    e.events().publish(topics, Cancelled { owner, reason_hash });
+}
+
+pub(crate) fn voided(e: &Env, token_id: u32, voided_by: Address, reason_hash: BytesN<32>) {
+    let topics = (symbol_short!("voided"), token_id);
+    let event = Voided {
+        voided_by,
+        reason_hash,
+    };
+    e.events().publish(topics, event);
+}
+
+pub(crate) fn fee_collected(e: &Env, token_id: u32, from: Address, token: Address, amount: i128) {
+    let topics = (Symbol::new(e, "fee_collected"), token_id);
+    let event = FeeCollected {
+        from,
+        token,
+        amount,
+    };
+    e.events().publish(topics, event);
 }
 
-pub(crate) fn transfer(e: &Env, from: Address, to: Address, amount: i128) {
-    let topics = (symbol_short!("transfer"), from, to);
-    e.events().publish(topics, amount);
+pub(crate) fn set_creation_fee(e: &Env, admin: Address, token: Address, amount: i128) {
+    let topics = (Symbol::new(e, "set_creation_fee"), admin);
+    e.events().publish(topics, CreationFeeSet { token, amount });
 }
 
-pub(crate) fn mint(e: &Env, to: &Address, token_id: u32) {
-    let topics = (symbol_short!("mint"), token_id);
-    e.events().publish(topics, token_id);
+pub(crate) fn set_unique_hashes(e: &Env, admin: Address, enabled: bool) {
+    let topics = (Symbol::new(e, "set_unique_hashes"), admin);
+    e.events().publish(topics, UniqueHashesSet { enabled });
 }
 
-pub(crate) fn clawback(e: &Env, admin: Address, from: Address, amount: i128) {
-    let topics = (symbol_short!("clawback"), admin, from);
-    e.events().publish(topics, amount);
+pub(crate) fn withdraw_fees(
+    e: &Env,
+    admin: Address,
+    treasury: Address,
+    token: Address,
+    amount: i128,
+) {
+    let topics = (Symbol::new(e, "withdraw_fees"), admin);
+    let event = FeesWithdrawn {
+        treasury,
+        token,
+        amount,
+    };
+    e.events().publish(topics, event);
 }
 
-pub(crate) fn set_authorized(e: &Env, admin: Address, id: Address, authorize: bool) {
-    let topics = (Symbol::new(e, "set_authorized"), admin, id);
-    e.events().publish(topics, authorize);
+pub(crate) fn delegation_added(
+    e: &Env,
+    delegator: Address,
+    delegate: Address,
+    scope: DelegationScope,
+    expires_at: u64,
+) {
+    let topics = (Symbol::new(e, "delegation_added"), delegator);
+    let event = DelegationAdded {
+        delegate,
+        scope,
+        expires_at,
+    };
+    e.events().publish(topics, event);
 }
 
-pub(crate) fn set_admin(e: &Env, admin: Address, new_admin: Address) {
-    let topics = (symbol_short!("set_admin"), admin);
-    e.events().publish(topics, new_admin);
+pub(crate) fn delegation_revoked(e: &Env, delegator: Address, delegate: Address) {
+    let topics = (Symbol::new(e, "delegation_revoked"), delegator);
+    e.events().publish(topics, DelegationRevoked { delegate });
 }
 
-pub(crate) fn burn(e: &Env, from: Address, amount: i128) {
-    let topics = (symbol_short!("burn"), from);
-    e.events().publish(topics, amount);
+pub(crate) fn amended(
+    e: &Env,
+    token_id: u32,
+    version: u32,
+    document_hash: BytesN<32>,
+    previous_hash: BytesN<32>,
+    uri: String,
+    hash_algorithm: HashAlgorithm,
+    file_count: u32,
+) {
+    let topics = (symbol_short!("amended"), token_id);
+    let event = Amended {
+        version,
+        document_hash,
+        previous_hash,
+        uri,
+        hash_algorithm,
+        file_count,
+    };
+    e.events().publish(topics, event);
 }
@@ -0,0 +1,27 @@
+use soroban_sdk::{token, Address, Env};
+
+use crate::storage_types::{CreationFee, DataKey};
+
+pub fn read_creation_fee(e: &Env) -> Option<CreationFee> {
+    let key = DataKey::CreationFee;
+    e.storage().instance().get(&key)
+}
+
+pub fn write_creation_fee(e: &Env, fee: &CreationFee) {
+    let key = DataKey::CreationFee;
+    e.storage().instance().set(&key, fee);
+}
+
+pub fn collect_creation_fee(e: &Env, from: &Address, fee: &CreationFee) {
+    let client = token::Client::new(e, &fee.token);
+    client.transfer(from, &e.current_contract_address(), &fee.amount);
+}
+
+pub fn withdraw_balance(e: &Env, token: &Address, to: &Address) -> i128 {
+    let client = token::Client::new(e, token);
+    let balance = client.balance(&e.current_contract_address());
+    if balance > 0 {
+        client.transfer(&e.current_contract_address(), to, &balance);
+    }
+    balance
+}
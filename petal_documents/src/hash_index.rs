@@ -0,0 +1,29 @@
+use soroban_sdk::{BytesN, Env, Vec};
+
+use crate::storage_types::DataKey;
+use crate::ttl::bump_persistent;
+
+pub fn read_tokens_by_hash(e: &Env, hash: BytesN<32>) -> Vec<u32> {
+    let key = DataKey::TokensByHash(hash);
+    e.storage().persistent().get(&key).unwrap_or(Vec::new(e))
+}
+
+pub fn index_hash(e: &Env, hash: BytesN<32>, token_id: u32) {
+    let mut token_ids = read_tokens_by_hash(e, hash.clone());
+    if !token_ids.contains(token_id) {
+        token_ids.push_back(token_id);
+        let key = DataKey::TokensByHash(hash);
+        e.storage().persistent().set(&key, &token_ids);
+        bump_persistent(e, &key);
+    }
+}
+
+pub fn read_unique_hashes(e: &Env) -> bool {
+    let key = DataKey::UniqueHashes;
+    e.storage().instance().get(&key).unwrap_or(false)
+}
+
+pub fn write_unique_hashes(e: &Env, enabled: bool) {
+    let key = DataKey::UniqueHashes;
+    e.storage().instance().set(&key, &enabled);
+}
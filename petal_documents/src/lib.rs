@@ -1,21 +1,74 @@
 #![no_std]
+#![allow(clippy::too_many_arguments)]
 
 mod storage_types;
 use crate::storage_types::{
-    INSTANCE_BUMP_AMOUNT_HIGH_WATERMARK, INSTANCE_BUMP_AMOUNT_LOW_WATERMARK,
+    CreationFee, Delegation, DocumentClosure, DocumentMatch, DocumentPage, DocumentRecord,
+    DocumentVersion, DocumentView, Rejection, RevokedReceipt, SignatureReceipt,
+    SignatureRevocation, SignerEntry, SignerReplacement, SignerView, SigningProgress,
+};
+
+mod document;
+use crate::document::{
+    add_signer_entry, append_token, count_rejected, count_required, count_signed, current_state,
+    next_token_id, next_waiting_signer, read_closure, read_document, read_receipt, read_rejection,
+    read_rejections, read_replacements, read_revocations, read_signature, read_signer_entries,
+    read_signer_entry, read_signers, read_signings, read_token_by_index, read_token_count,
+    read_version, remove_receipt, remove_rejection, remove_signature, required_signatures,
+    resolve_state, snapshot_version, write_closure, write_document, write_last_token_id,
+    write_receipt, write_rejection, write_replacements, write_revocations, write_signature,
+    write_signer_entry, write_signers, write_version,
 };
 
 mod erc_functions;
-use crate::erc_functions::exists;
+use crate::erc_functions::{exists, owner_of};
 
 mod event;
+use crate::event::RejectionReason;
+
+mod nonce;
+use crate::nonce::{increment_nonce, read_nonce};
+
+mod signature;
+use crate::signature::{account_address, signature_payload};
+
+mod fee;
+use crate::fee::{collect_creation_fee, read_creation_fee, withdraw_balance, write_creation_fee};
+
+mod delegation;
+use crate::delegation::{
+    is_delegate_for, read_delegates, read_delegation, remove_delegation, write_delegation,
+};
+
+mod address_index;
+use crate::address_index::{
+    index_owner, index_signer, read_owner_document, read_owner_document_count,
+    read_signer_document, read_signer_document_count, unindex_signer,
+};
+
+mod hash_index;
+use crate::hash_index::{index_hash, read_tokens_by_hash, read_unique_hashes, write_unique_hashes};
+
+mod ttl;
+use crate::ttl::{bump_document, bump_document_entries, bump_instance};
+
+mod merkle;
+use crate::merkle::{compute_root, tree_depth};
 
 mod admin;
 use crate::admin::{has_administrator, read_administrator, write_administrator};
 
+mod migration;
+use crate::migration::{
+    has_legacy_storage, migrate_legacy_storage, read_storage_version, write_storage_version,
+    STORAGE_VERSION,
+};
+
+mod test;
+
 use soroban_sdk::{
-    contract, contracterror, contractimpl, contracttype, log, panic_with_error, symbol_short,
-    Address, Env, Map, String, Symbol, Vec,
+    contract, contracterror, contractimpl, contracttype, panic_with_error, symbol_short,
+    xdr::FromXdr, Address, Bytes, BytesN, Env, Map, String, Symbol, Vec,
 };
 
 // mod erc721 {
@@ -46,6 +99,35 @@ pub enum Error {
     TokenAlreadyMinted = 13,
     TokenDoesNotExist = 14,
     SignersListEmpty = 15,
+    MigrationPending = 16,
+    InvalidSignedMessage = 17,
+    SignerKeyMismatch = 18,
+    InvalidNonce = 19,
+    InvalidFeeAmount = 20,
+    SignerAlreadyExists = 21,
+    DocumentAlreadyCompleted = 22,
+    SignerAlreadyActed = 23,
+    DocumentRejected = 24,
+    DocumentCancelled = 25,
+    DocumentVoided = 26,
+    InvalidStateTransition = 27,
+    NotAuthorizedToVoid = 28,
+    NotSignersTurn = 29,
+    InvalidThreshold = 30,
+    InvalidRole = 31,
+    WitnessedSignerNotSigned = 32,
+    InvalidDelegation = 33,
+    DelegationNotFound = 34,
+    NotADelegate = 35,
+    SignatureNotRevocable = 36,
+    InvalidRejectionReason = 37,
+    InvalidAmendment = 38,
+    InvalidFileCount = 39,
+    InvalidHashAlgorithm = 40,
+    DuplicateDocumentHash = 41,
+    TooManyDocuments = 42,
+    InvalidSignatureStatus = 43,
+    ExplicitTokenIdNotAllowed = 44,
 }
 
 #[derive(Clone, Debug, PartialEq, Eq)]
@@ -57,12 +139,84 @@ pub enum SignatureStatus {
     Waiting,
 }
 
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[contracttype]
+pub enum SignerRole {
+    Signer,
+    Approver,
+    Witness(Address),
+    Viewer,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[contracttype]
+pub enum DelegationScope {
+    AllDocuments,
+    Document(u32),
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[contracttype]
+pub enum ActedBy {
+    Signer,
+    Delegate(Address),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[contracttype]
+pub enum DocumentState {
+    Open,
+    Completed,
+    Rejected,
+    Expired,
+    Cancelled,
+    Voided,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[contracttype]
+pub enum SigningOrder {
+    Parallel,
+    Sequential,
+}
+
+/// How a document hash was derived. `Sha256Merkle` roots are built over
+/// leaves hashed as `sha256(0x00 || file_hash)` and nodes hashed as
+/// `sha256(0x01 || left || right)`, with each pair in ascending order.
+/// `LegacyText` marks documents migrated from the text identifiers of the
+/// first release, stored as the sha256 of that text.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[contracttype]
+pub enum HashAlgorithm {
+    Sha256,
+    Sha256Merkle,
+    LegacyText,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[contracttype]
+pub enum RejectionPolicy {
+    Terminate,
+    Continue,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[contracttype]
+pub struct DocumentOptions {
+    pub signing_order: SigningOrder,
+    pub threshold: u32,
+    pub roles: Map<Address, SignerRole>,
+    pub rejection_policy: RejectionPolicy,
+    pub hash_algorithm: HashAlgorithm,
+    pub file_count: u32,
+}
+
 #[derive(Clone, Debug)]
 #[contracttype]
 pub struct SignedMessage {
     pub deadline: u64,
     pub description: String,
-    pub document_hash: String,
+    pub document_hash: BytesN<32>,
     pub document_uri: String,
     pub signer: Address,
     pub status: SignatureStatus,
@@ -70,279 +224,521 @@ pub struct SignedMessage {
     pub nonce: u32,
 }
 
-const OWNERS: Symbol = symbol_short!("OWNERS");
-const URIS: Symbol = symbol_short!("URIS");
-
-const NONCES: Symbol = symbol_short!("NONCES");
-const T2DHASH: Symbol = symbol_short!("T2DHASH");
-const DEADLINES: Symbol = symbol_short!("DEADLINES");
-const DOCSIGN: Symbol = symbol_short!("DOCSIGN");
-const CREACTION_FEE: Symbol = symbol_short!("crea_fee");
-
 const TEST: Symbol = symbol_short!("TEST");
 
+// A full view of a five-signer document costs about 5.2M CPU instructions and
+// 1.3MB of memory on-chain, so a page of eight stays near 42M instructions and
+// 10MB, well inside the transaction limits.
+const MAX_PAGE_SIZE: u32 = 8;
+
 #[contractimpl]
 impl PetalDocuments {
+    /// Documents minted without an explicit id are numbered from
+    /// `token_id + 1`.
     pub fn init(e: Env, admin: Address, token_id: u32) {
         if has_administrator(&e) {
             panic!("already initialized")
         }
 
         write_administrator(&e, &admin);
+        write_last_token_id(&e, token_id);
+        if !has_legacy_storage(&e) {
+            write_storage_version(&e, STORAGE_VERSION);
+        }
+        bump_instance(&e);
+    }
+
+    pub fn upgrade(e: Env, new_wasm_hash: BytesN<32>) {
+        let admin = read_administrator(&e);
+        admin.require_auth();
+
+        e.deployer().update_current_contract_wasm(new_wasm_hash);
     }
 
+    pub fn migrate_storage(e: Env, batch_size: u32) -> u32 {
+        let admin = read_administrator(&e);
+        admin.require_auth();
+
+        migrate_legacy_storage(&e, batch_size)
+    }
+
+    pub fn get_storage_version(e: Env) -> u32 {
+        read_storage_version(&e)
+    }
+
+    /// Records `signer`'s decision. When `delegate` is set the delegate
+    /// authorizes the call instead and is recorded as acting on the signer's
+    /// behalf. A rejection may carry the hash of its reason.
     pub fn sign_document(
         e: Env,
-        document_hash: String,
+        document_hash: BytesN<32>,
         signer: Address,
         status: SignatureStatus,
         token_id: u32,
-    ) -> Map<u32, Map<Address, SignatureStatus>> {
-        // let client = erc721::Client::new(&e, &erc721_address);
-        // let is_token_minted: bool = client.require_minted(&payload.token_id);
-        let is_token_minted: bool = Self::require_minted(&e, token_id);
-        if is_token_minted == false {
-            panic_with_error!(&e, Error::TokenNotMinted)
-        }
-        let mut doc_signings: Map<u32, Map<Address, SignatureStatus>> = e
-            .storage()
-            .persistent()
-            .get(&DOCSIGN)
-            .unwrap_or(Map::new(&e));
-        if doc_signings.is_empty() {
-            panic_with_error!(&e, Error::DocumentSigningsIsEmpty)
-        }
-
-        let clone_signer = signer.clone();
-        let all_signings = doc_signings.get(token_id);
-        let signer_status: SignatureStatus = match all_signings {
-            Some(signing) => {
-                let is_signer = signing.get(signer);
-                match is_signer {
-                    Some(signer) => {
-                        if signer == SignatureStatus::NotASigner {
-                            panic_with_error!(&e, Error::NotASigner)
-                        } else if signer == SignatureStatus::Signed {
-                            panic_with_error!(&e, Error::AlreadySigned)
-                        }
-                        signer
-                    }
-                    None => {
-                        panic_with_error!(&e, Error::SignerDoesNotExist)
-                    }
+        delegate: Option<Address>,
+        reason_hash: Option<BytesN<32>>,
+    ) -> Map<Address, SignatureStatus> {
+        if reason_hash.is_some() && status != SignatureStatus::Rejected {
+            panic_with_error!(&e, Error::InvalidRejectionReason)
+        }
+
+        let (acted_by, submitter) = match delegate {
+            Some(delegate) => {
+                delegate.require_auth();
+                if !is_delegate_for(&e, signer.clone(), delegate.clone(), token_id) {
+                    panic_with_error!(&e, Error::NotADelegate)
                 }
+                (ActedBy::Delegate(delegate.clone()), delegate)
             }
             None => {
-                panic_with_error!(&e, Error::DocumentSigningsIsEmpty)
+                signer.require_auth();
+                (ActedBy::Signer, signer.clone())
             }
         };
 
-        let token_to_doc_hashes: Map<u32, String> = e
-            .storage()
-            .persistent()
-            .get(&T2DHASH)
-            .unwrap_or(Map::new(&e));
-        if token_to_doc_hashes.is_empty() {
-            panic_with_error!(&e, Error::DocumentHashesIsEmpty)
+        Self::record_signature(
+            &e,
+            document_hash,
+            signer,
+            status,
+            token_id,
+            acted_by,
+            submitter,
+            reason_hash,
+        )
+    }
+
+    /// Records a decision signed off-chain by the signer's key over
+    /// `signature_payload(message)`, which binds `message` to this network
+    /// and contract. A `relayer` submitting on the signer's behalf authorizes
+    /// the call and is noted on the signature receipt.
+    pub fn sign_document_with_signature(
+        e: Env,
+        message: Bytes,
+        public_key: BytesN<32>,
+        signature: BytesN<64>,
+        relayer: Option<Address>,
+    ) -> Map<Address, SignatureStatus> {
+        let payload = signature_payload(&e, &message);
+        e.crypto().ed25519_verify(&public_key, &payload, &signature);
+
+        let signed_message = match SignedMessage::from_xdr(&e, &message) {
+            Ok(signed_message) => signed_message,
+            Err(_) => panic_with_error!(&e, Error::InvalidSignedMessage),
+        };
+        if account_address(&e, &public_key) != signed_message.signer {
+            panic_with_error!(&e, Error::SignerKeyMismatch)
+        }
+        if e.ledger().timestamp() > signed_message.deadline {
+            panic_with_error!(&e, Error::SignatureExpired)
+        }
+        if signed_message.nonce != read_nonce(&e, signed_message.signer.clone()) {
+            panic_with_error!(&e, Error::InvalidNonce)
         }
 
-        let doc_hash = token_to_doc_hashes.get(token_id);
-        let matched_hash = match doc_hash {
-            Some(hash) => {
-                if (hash != document_hash) {
-                    panic_with_error!(&e, Error::DocumentHashesDoesNotMatchTokenHash)
-                }
-                hash
-            }
-            None => {
-                panic_with_error!(&e, Error::HashNotFound)
+        let submitter = match relayer {
+            Some(relayer) => {
+                relayer.require_auth();
+                relayer
             }
+            None => signed_message.signer.clone(),
         };
 
-        let doc_signing_deadlines: Map<u32, u64> = e
-            .storage()
-            .persistent()
-            .get(&DEADLINES)
-            .unwrap_or(Map::new(&e));
-        if doc_signing_deadlines.is_empty() {
-            panic_with_error!(&e, Error::DeadlinesIsEmpty)
-        }
-        let deadlines: Option<u64> = doc_signing_deadlines.get(token_id);
-        let deadline: u64 = match deadlines {
-            Some(v) => {
-                if e.ledger().timestamp() > v {
-                    panic_with_error!(&e, Error::DeadlinePassed)
-                }
-                v
-            }
-            None => {
-                panic_with_error!(&e, Error::DeadlineNotFound)
-            }
+        Self::record_signature(
+            &e,
+            signed_message.document_hash,
+            signed_message.signer,
+            signed_message.status,
+            signed_message.token_id,
+            ActedBy::Signer,
+            submitter,
+            None,
+        )
+    }
+
+    fn record_signature(
+        e: &Env,
+        document_hash: BytesN<32>,
+        signer: Address,
+        status: SignatureStatus,
+        token_id: u32,
+        acted_by: ActedBy,
+        submitter: Address,
+        reason_hash: Option<BytesN<32>>,
+    ) -> Map<Address, SignatureStatus> {
+        if status != SignatureStatus::Signed && status != SignatureStatus::Rejected {
+            panic_with_error!(e, Error::InvalidSignatureStatus)
+        }
+        let mut document = match read_document(e, token_id) {
+            Some(document) => document,
+            None => panic_with_error!(e, Error::TokenNotMinted),
         };
+        Self::require_open(e, &document);
 
-        let clone_signer_2 = clone_signer.clone();
-        Self::verify_signer(&e, clone_signer, token_id);
+        match read_signature(e, token_id, signer.clone()) {
+            Some(SignatureStatus::NotASigner) => panic_with_error!(e, Error::NotASigner),
+            Some(SignatureStatus::Signed) => panic_with_error!(e, Error::AlreadySigned),
+            Some(_) => {}
+            None => panic_with_error!(e, Error::SignerDoesNotExist),
+        }
 
-        if e.ledger().timestamp() > deadline {
-            panic_with_error!(&e, Error::SignatureExpired)
+        if document.document_hash != document_hash {
+            panic_with_error!(e, Error::DocumentHashesDoesNotMatchTokenHash)
+        }
+
+        Self::verify_signer(e, &document, signer.clone(), token_id);
+
+        let receipt = SignatureReceipt {
+            status: status.clone(),
+            document_hash,
+            nonce: increment_nonce(e, signer.clone()),
+            timestamp: e.ledger().timestamp(),
+            sequence: e.ledger().sequence(),
+            submitter: submitter.clone(),
         };
+        write_receipt(e, token_id, signer.clone(), &receipt);
+        write_signature(e, token_id, signer.clone(), &status, acted_by.clone());
 
-        let clone_signer_3 = clone_signer_2.clone();
-        let clone_signer_4 = clone_signer_3.clone();
-        let mut signature_nonces: Map<Address, u32> = e
-            .storage()
-            .persistent()
-            .get(&NONCES)
-            .unwrap_or(Map::new(&e));
-        let last_nonce = signature_nonces.get(clone_signer_4).unwrap_or(0);
-        if signature_nonces.is_empty() {
-            signature_nonces.set(clone_signer_2, last_nonce);
-        } else {
-            signature_nonces.set(clone_signer_2, last_nonce + 1);
+        match status {
+            SignatureStatus::Signed => {
+                event::signed(e, token_id, signer, acted_by, submitter, receipt.nonce)
+            }
+            SignatureStatus::Rejected => {
+                let reason = match reason_hash {
+                    Some(reason_hash) => {
+                        let rejection = Rejection {
+                            reason_hash: reason_hash.clone(),
+                            timestamp: e.ledger().timestamp(),
+                        };
+                        write_rejection(e, token_id, signer.clone(), &rejection);
+                        RejectionReason::Hash(reason_hash)
+                    }
+                    None => RejectionReason::Unspecified,
+                };
+                event::rejected(
+                    e,
+                    token_id,
+                    signer,
+                    acted_by,
+                    submitter,
+                    receipt.nonce,
+                    reason,
+                )
+            }
+            _ => {}
         }
-        let status_copy = status.clone();
-        // doc_signings.get(token_id).unwrap().set(clone_signer_3, status);
-        let mut inner_signings: Map<Address, SignatureStatus> = doc_signings.get(token_id).unwrap();
-        // inner_signings.set(clone_signer_3, SignatureStatus::Signed);
-        inner_signings.set(clone_signer_3, status);
-        doc_signings.set(token_id, inner_signings);
+        Self::update_state(e, token_id, &mut document);
+        bump_document(e, token_id);
 
-        e.storage().persistent().set(&DOCSIGN, &doc_signings);
-        // e.storage().persistent().bump(34560);
+        read_signings(e, token_id)
+    }
 
-        doc_signings
+    fn verify_signer(e: &Env, document: &DocumentRecord, signer: Address, token_id: u32) {
+        let entry = read_signer_entry(e, token_id, signer.clone()).unwrap();
+        if entry.status != SignatureStatus::Waiting {
+            panic_with_error!(e, Error::AlreadySigned)
+        }
+
+        if let SignerRole::Witness(witnessed) = entry.role {
+            if read_signature(e, token_id, witnessed) != Some(SignatureStatus::Signed) {
+                panic_with_error!(e, Error::WitnessedSignerNotSigned)
+            }
+        }
+
+        if document.signing_order == SigningOrder::Sequential
+            && next_waiting_signer(e, token_id) != Some(signer)
+        {
+            panic_with_error!(e, Error::NotSignersTurn)
+        }
     }
 
-    fn verify_signer(e: &Env, signer: Address, token_id: u32) {
+    /// Returns a signer to `Waiting` while the document is still open. A
+    /// signature that a witness has already attested cannot be revoked.
+    pub fn revoke_signature(e: Env, doc_id: u32, signer: Address) -> Map<Address, SignatureStatus> {
         signer.require_auth();
 
-        let mut doc_signings: Map<u32, Map<Address, SignatureStatus>> = e
-            .storage()
-            .persistent()
-            .get(&DOCSIGN)
-            .unwrap_or(Map::new(&e));
-        let mut inner_doc_signings: Map<Address, SignatureStatus> =
-            doc_signings.get(token_id).unwrap();
-        let mut current_signature_status: SignatureStatus = inner_doc_signings.get(signer).unwrap();
+        let mut document = match read_document(&e, doc_id) {
+            Some(document) => document,
+            None => panic_with_error!(&e, Error::TokenDoesNotExist),
+        };
+        Self::require_open(&e, &document);
 
-        if (current_signature_status != SignatureStatus::Waiting) {
-            panic_with_error!(&e, Error::AlreadySigned)
+        let entries = read_signer_entries(&e, doc_id);
+        match entries.get(signer.clone()) {
+            Some(entry) if entry.status == SignatureStatus::Signed => {}
+            Some(_) => panic_with_error!(&e, Error::SignatureNotRevocable),
+            None => panic_with_error!(&e, Error::SignerDoesNotExist),
         }
+        for entry in entries.values().iter() {
+            if entry.role == SignerRole::Witness(signer.clone())
+                && entry.status == SignatureStatus::Signed
+            {
+                panic_with_error!(&e, Error::SignatureNotRevocable)
+            }
+        }
+
+        let nonce = increment_nonce(&e, signer.clone());
+        let revoked = match read_receipt(&e, doc_id, signer.clone()) {
+            Some(receipt) => RevokedReceipt::Receipt(receipt),
+            None => RevokedReceipt::Legacy,
+        };
+        let mut revocations = read_revocations(&e, doc_id, signer.clone());
+        revocations.push_back(SignatureRevocation {
+            revoked,
+            nonce,
+            timestamp: e.ledger().timestamp(),
+            sequence: e.ledger().sequence(),
+        });
+        write_revocations(&e, doc_id, signer.clone(), &revocations);
+        remove_receipt(&e, doc_id, signer.clone());
+        write_signature(
+            &e,
+            doc_id,
+            signer.clone(),
+            &SignatureStatus::Waiting,
+            ActedBy::Signer,
+        );
+        Self::update_state(&e, doc_id, &mut document);
+        bump_document(&e, doc_id);
+
+        event::signature_revoked(&e, doc_id, signer, nonce);
+        read_signings(&e, doc_id)
+    }
+
+    /// The signer expected to act next on a sequential document. Parallel
+    /// documents have no fixed turn and always return `None`.
+    pub fn next_signer(e: Env, doc_id: u32) -> Option<Address> {
+        let document = match read_document(&e, doc_id) {
+            Some(document) => document,
+            None => panic_with_error!(&e, Error::TokenDoesNotExist),
+        };
+        if document.signing_order != SigningOrder::Sequential
+            || current_state(&e, &document) != DocumentState::Open
+        {
+            return None;
+        }
+        next_waiting_signer(&e, doc_id)
     }
 
+    /// Mints a document owned by `to` under the next free id and returns it.
+    /// `creator` authorizes the mint and pays the creation fee unless they are
+    /// the admin. Supplying `token_id` is reserved to the admin for carrying
+    /// over documents that were numbered outside the contract.
     pub fn safe_mint(
         e: Env,
+        creator: Address,
         to: Address,
-        token_id: u32,
+        token_id: Option<u32>,
         meta_uri: String,
         signers: Vec<Address>,
-        document_hash: String,
+        document_hash: BytesN<32>,
         deadline: u64,
+        options: Option<DocumentOptions>,
     ) -> u32 {
-        // IMPLEMENT THIS LIKE IN SOLIDITY PETAL DOCUMENTS CONTRACT
-        //		require(
-        // 	msg.value >= creationFee || owner() == msg.sender,
-        // 	'Creation fee not met'
-        // );
-
+        creator.require_auth();
         if signers.is_empty() {
             panic_with_error!(&e, Error::SignersListEmpty)
         }
-        // let client = erc721::Client::new(&e, &erc721_address);
-        // client.mint(&token_id, &to);
-        // client.set_token_uri(&token_id, &meta_uri);
-
-        Self::mint(&e, token_id, to);
-        Self::set_token_uri(&e, token_id, meta_uri);
-
-        let mut token_to_doc_hashes: Map<u32, String> = e
-            .storage()
-            .persistent()
-            .get(&T2DHASH)
-            .unwrap_or(Map::new(&e));
-        token_to_doc_hashes.set(token_id, document_hash);
-
-        let mut doc_signing_deadlines: Map<u32, u64> = e
-            .storage()
-            .persistent()
-            .get(&DEADLINES)
-            .unwrap_or(Map::new(&e));
-        doc_signing_deadlines.set(token_id, deadline);
-
-        let mut doc_signings: Map<u32, Map<Address, SignatureStatus>> = e
-            .storage()
-            .persistent()
-            .get(&DOCSIGN)
-            .unwrap_or(Map::new(&e));
-        let mut inner_doc_signings: Map<Address, SignatureStatus> = Map::new(&e);
+        if has_legacy_storage(&e) {
+            panic_with_error!(&e, Error::MigrationPending)
+        }
+
+        let options = options.unwrap_or(DocumentOptions {
+            signing_order: SigningOrder::Parallel,
+            threshold: 0,
+            roles: Map::new(&e),
+            rejection_policy: RejectionPolicy::Terminate,
+            hash_algorithm: HashAlgorithm::Sha256,
+            file_count: 1,
+        });
+        Self::require_valid_envelope(&e, options.hash_algorithm, options.file_count);
+
+        let token_id = match token_id {
+            Some(token_id) => {
+                if creator != read_administrator(&e) {
+                    panic_with_error!(&e, Error::ExplicitTokenIdNotAllowed)
+                }
+                token_id
+            }
+            None => next_token_id(&e),
+        };
 
+        let document = DocumentRecord {
+            owner: to.clone(),
+            uri: meta_uri,
+            document_hash,
+            deadline,
+            state: DocumentState::Open,
+            signing_order: options.signing_order,
+            threshold: options.threshold,
+            rejection_policy: options.rejection_policy,
+            version: 1,
+            previous_hash: BytesN::from_array(&e, &[0; 32]),
+            hash_algorithm: options.hash_algorithm,
+            file_count: options.file_count,
+        };
+        Self::mint(&e, token_id, &document);
+        Self::charge_creation_fee(&e, &creator, token_id);
+
+        let mut unique_signers: Vec<Address> = Vec::new(&e);
         for signer in signers.iter() {
-            inner_doc_signings.set(signer, SignatureStatus::Waiting);
+            if !unique_signers.contains(&signer) {
+                unique_signers.push_back(signer);
+            }
         }
-        doc_signings.set(token_id, inner_doc_signings);
 
-        e.storage().persistent().set(&T2DHASH, &token_to_doc_hashes);
-        e.storage()
-            .persistent()
-            .set(&DEADLINES, &doc_signing_deadlines);
-        e.storage().persistent().set(&DOCSIGN, &doc_signings);
+        let mut required_signers: u32 = 0;
+        for (participant, role) in options.roles.iter() {
+            if !unique_signers.contains(&participant) {
+                panic_with_error!(&e, Error::InvalidRole)
+            }
+            if let SignerRole::Witness(witnessed) = role {
+                if witnessed == participant || !unique_signers.contains(&witnessed) {
+                    panic_with_error!(&e, Error::InvalidRole)
+                }
+            }
+        }
+        for signer in unique_signers.iter() {
+            let role = options
+                .roles
+                .get(signer.clone())
+                .unwrap_or(SignerRole::Signer);
+            if role != SignerRole::Viewer {
+                required_signers += 1;
+            }
+            add_signer_entry(&e, token_id, signer.clone(), role.clone());
+            index_signer(&e, signer.clone(), token_id);
+            event::signer_added(&e, token_id, signer, role);
+        }
+        if required_signers == 0 {
+            panic_with_error!(&e, Error::SignersListEmpty)
+        }
+        if options.threshold > required_signers {
+            panic_with_error!(&e, Error::InvalidThreshold)
+        }
+        write_signers(&e, token_id, &unique_signers);
+        bump_document(&e, token_id);
 
-        // e.storage().persistent().bump(INSTANCE_BUMP_AMOUNT_LOW_WATERMARK, INSTANCE_BUMP_AMOUNT_HIGH_WATERMARK);
         token_id
     }
 
-    fn mint(e: &Env, token_id: u32, to: Address) {
-        // New Token id should be incremented by 1 and not injected as param.
+    fn charge_creation_fee(e: &Env, from: &Address, token_id: u32) {
+        let fee = match read_creation_fee(e) {
+            Some(fee) => fee,
+            None => return,
+        };
+        if fee.amount == 0 || (has_administrator(e) && *from == read_administrator(e)) {
+            return;
+        }
 
-        let mut owners: Map<u32, Address> = e
-            .storage()
-            .persistent()
-            .get(&OWNERS)
-            .unwrap_or(Map::new(&e));
-        if exists(&e, token_id, &owners) == true {
-            panic_with_error!(&e, Error::TokenAlreadyMinted)
+        collect_creation_fee(e, from, &fee);
+        event::fee_collected(e, token_id, from.clone(), fee.token, fee.amount);
+    }
+
+    pub fn set_creation_fee(e: Env, token: Address, amount: i128) {
+        if amount < 0 {
+            panic_with_error!(&e, Error::InvalidFeeAmount)
         }
-        let cloned_to = to.clone();
+        let admin = read_administrator(&e);
+        admin.require_auth();
+
+        write_creation_fee(
+            &e,
+            &CreationFee {
+                token: token.clone(),
+                amount,
+            },
+        );
+        event::set_creation_fee(&e, admin, token, amount);
+    }
 
-        owners.set(token_id, to);
-        log!(&e, "Owners set locally {}", owners);
+    pub fn get_creation_fee(e: Env) -> Option<CreationFee> {
+        read_creation_fee(&e)
+    }
 
-        e.storage().persistent().set(&OWNERS, &owners);
-        log!(&e, "Owners set instance {}", owners);
+    pub fn withdraw_fees(e: Env, token: Address, treasury: Address) -> i128 {
+        let admin = read_administrator(&e);
+        admin.require_auth();
 
-        // e.storage().persistent().bump(INSTANCE_BUMP_AMOUNT_LOW_WATERMARK, INSTANCE_BUMP_AMOUNT_HIGH_WATERMARK);
-        event::mint(&e, &cloned_to, token_id);
+        let amount = withdraw_balance(&e, &token, &treasury);
+        event::withdraw_fees(&e, admin, treasury, token, amount);
+        amount
     }
 
-    fn set_token_uri(e: &Env, token_id: u32, token_uri: String) {
-        let owners: Map<u32, Address> = e
-            .storage()
-            .persistent()
-            .get(&OWNERS)
-            .unwrap_or(Map::new(&e));
+    fn mint(e: &Env, token_id: u32, document: &DocumentRecord) {
+        if exists(e, token_id) {
+            panic_with_error!(e, Error::TokenAlreadyMinted)
+        }
+        Self::require_unique_hash(e, document.document_hash.clone());
+
+        write_document(e, token_id, document);
+        append_token(e, token_id);
+        index_hash(e, document.document_hash.clone(), token_id);
+        index_owner(e, document.owner.clone(), token_id);
+
+        event::document_created(
+            e,
+            token_id,
+            document.owner.clone(),
+            document.uri.clone(),
+            document.document_hash.clone(),
+            document.deadline,
+            document.signing_order,
+            document.threshold,
+            document.rejection_policy,
+            document.hash_algorithm,
+            document.file_count,
+        );
+    }
 
-        if exists(&e, token_id, &owners) == false {
-            panic_with_error!(&e, Error::TokenDoesNotExist)
+    fn require_valid_envelope(e: &Env, hash_algorithm: HashAlgorithm, file_count: u32) {
+        match hash_algorithm {
+            HashAlgorithm::Sha256 if file_count != 1 => {
+                panic_with_error!(e, Error::InvalidFileCount)
+            }
+            HashAlgorithm::Sha256Merkle if file_count == 0 => {
+                panic_with_error!(e, Error::InvalidFileCount)
+            }
+            HashAlgorithm::LegacyText => panic_with_error!(e, Error::InvalidHashAlgorithm),
+            _ => {}
         }
+    }
 
-        let mut token_uris: Map<u32, String> =
-            e.storage().persistent().get(&URIS).unwrap_or(Map::new(&e));
-        token_uris.set(token_id, token_uri);
+    fn require_unique_hash(e: &Env, hash: BytesN<32>) {
+        if read_unique_hashes(e) && !read_tokens_by_hash(e, hash).is_empty() {
+            panic_with_error!(e, Error::DuplicateDocumentHash)
+        }
+    }
 
-        e.storage().persistent().set(&URIS, &token_uris);
-        // e.storage().persistent().bump(INSTANCE_BUMP_AMOUNT_LOW_WATERMARK, INSTANCE_BUMP_AMOUNT_HIGH_WATERMARK);
+    /// When enabled, minting or amending to a hash that is already registered
+    /// is rejected.
+    pub fn set_unique_hashes(e: Env, enabled: bool) {
+        let admin = read_administrator(&e);
+        admin.require_auth();
+
+        write_unique_hashes(&e, enabled);
+        event::set_unique_hashes(&e, admin, enabled);
+    }
+
+    pub fn get_unique_hashes(e: Env) -> bool {
+        read_unique_hashes(&e)
     }
 
-    fn require_minted(e: &Env, token_id: u32) -> bool {
-        let owners: Map<u32, Address> = e
-            .storage()
-            .persistent()
-            .get(&OWNERS)
-            .unwrap_or(Map::new(&e));
-        if exists(&e, token_id, &owners) == true {
-            return true;
+    /// Every document registered under `hash`, whether as its current version
+    /// or as a version it has since been amended from.
+    pub fn find_by_hash(e: Env, hash: BytesN<32>) -> Vec<DocumentMatch> {
+        let mut matches: Vec<DocumentMatch> = Vec::new(&e);
+        for token_id in read_tokens_by_hash(&e, hash.clone()).iter() {
+            if let Some(document) = read_document(&e, token_id) {
+                matches.push_back(DocumentMatch {
+                    token_id,
+                    state: current_state(&e, &document),
+                    current: document.document_hash == hash,
+                    progress: Self::signing_progress(&e, token_id, &document),
+                });
+            }
         }
-        return false;
+        matches
     }
 
     pub fn set_test_int(e: Env) {
@@ -362,101 +758,715 @@ impl PetalDocuments {
     }
 
     pub fn get_nonces(e: Env, user: Address) -> u32 {
-        let nonces: Map<Address, u32> = e
-            .storage()
-            .persistent()
-            .get(&NONCES)
-            .unwrap_or(Map::new(&e));
-        let user_nonce = nonces.get(user).unwrap_or(0);
-        // e.storage().persistent().bump(INSTANCE_BUMP_AMOUNT_LOW_WATERMARK, INSTANCE_BUMP_AMOUNT_HIGH_WATERMARK);
-        user_nonce
+        read_nonce(&e, user)
     }
 
+    // The map getters below predate pagination and cover every document, so
+    // they refuse to run once there are more than `MAX_PAGE_SIZE` of them.
+    // `list_documents` pages through any number.
+
     pub fn get_owners(e: Env) -> Map<u32, Address> {
-        let owners: Map<u32, Address> = e
-            .storage()
-            .persistent()
-            .get(&OWNERS)
-            .unwrap_or(Map::new(&e));
+        let mut owners: Map<u32, Address> = Map::new(&e);
+        for token_id in Self::token_ids(&e).iter() {
+            owners.set(token_id, owner_of(&e, token_id));
+        }
         owners
     }
 
     pub fn get_token_uris(e: Env) -> Map<u32, String> {
-        let token_uris: Map<u32, String> =
-            e.storage().persistent().get(&URIS).unwrap_or(Map::new(&e));
+        let mut token_uris: Map<u32, String> = Map::new(&e);
+        for token_id in Self::token_ids(&e).iter() {
+            if let Some(document) = read_document(&e, token_id) {
+                token_uris.set(token_id, document.uri);
+            }
+        }
         token_uris
     }
 
     pub fn get_token_uri(e: Env, doc_id: u32) -> String {
-        let token_uris: Map<u32, String> =
-            e.storage().persistent().get(&URIS).unwrap_or(Map::new(&e));
-        let token_uri = token_uris.get(doc_id).unwrap();
-        token_uri
-    }
-
-    pub fn get_td_hashes(e: Env) -> Map<u32, String> {
-        let token_to_doc_hashes: Map<u32, String> = e
-            .storage()
-            .persistent()
-            .get(&T2DHASH)
-            .unwrap_or(Map::new(&e));
+        match read_document(&e, doc_id) {
+            Some(document) => document.uri,
+            None => panic_with_error!(&e, Error::TokenDoesNotExist),
+        }
+    }
+
+    pub fn get_td_hashes(e: Env) -> Map<u32, BytesN<32>> {
+        let mut token_to_doc_hashes: Map<u32, BytesN<32>> = Map::new(&e);
+        for token_id in Self::token_ids(&e).iter() {
+            if let Some(document) = read_document(&e, token_id) {
+                token_to_doc_hashes.set(token_id, document.document_hash);
+            }
+        }
         token_to_doc_hashes
     }
 
     pub fn get_deadlines(e: Env) -> Map<u32, u64> {
-        let deadlines: Map<u32, u64> = e
-            .storage()
-            .persistent()
-            .get(&DEADLINES)
-            .unwrap_or(Map::new(&e));
+        let mut deadlines: Map<u32, u64> = Map::new(&e);
+        for token_id in Self::token_ids(&e).iter() {
+            if let Some(document) = read_document(&e, token_id) {
+                deadlines.set(token_id, document.deadline);
+            }
+        }
         deadlines
     }
 
     pub fn get_documents(e: Env) -> Map<u32, Map<Address, SignatureStatus>> {
-        let doc_signings: Map<u32, Map<Address, SignatureStatus>> = e
-            .storage()
-            .persistent()
-            .get(&DOCSIGN)
-            .unwrap_or(Map::new(&e));
+        let mut doc_signings: Map<u32, Map<Address, SignatureStatus>> = Map::new(&e);
+        for token_id in Self::token_ids(&e).iter() {
+            doc_signings.set(token_id, read_signings(&e, token_id));
+        }
         doc_signings
     }
 
-    pub fn get_document(e: Env, doc_id: u32) -> Map<Address, SignatureStatus> {
-        let doc_signings: Map<u32, Map<Address, SignatureStatus>> = e
-            .storage()
-            .persistent()
-            .get(&DOCSIGN)
-            .unwrap_or(Map::new(&e));
-        let document = doc_signings.get(doc_id).unwrap_or(Map::new(&e));
+    pub fn get_document(e: Env, doc_id: u32) -> Map<Address, SignerEntry> {
+        bump_document(&e, doc_id);
+        read_signer_entries(&e, doc_id)
+    }
+
+    /// Lists up to `limit` documents, capped at `MAX_PAGE_SIZE`, starting at
+    /// position `cursor` in mint order.
+    pub fn list_documents(e: Env, cursor: u32, limit: u32) -> DocumentPage {
+        Self::indexed_page(
+            &e,
+            read_token_count(&e),
+            cursor,
+            limit,
+            |index| read_token_by_index(&e, index),
+            |_, _| true,
+        )
+    }
+
+    /// Documents created by `owner`, paginated like `list_documents`.
+    pub fn documents_for_owner(e: Env, owner: Address, cursor: u32, limit: u32) -> DocumentPage {
+        Self::indexed_page(
+            &e,
+            read_owner_document_count(&e, owner.clone()),
+            cursor,
+            limit,
+            |index| read_owner_document(&e, owner.clone(), index),
+            |_, _| true,
+        )
+    }
+
+    /// Documents `signer` takes part in, paginated like `list_documents`.
+    /// With a `status_filter` only documents where the signer currently has
+    /// that status are returned; filtering on `Waiting` further keeps only
+    /// open documents, those still awaiting the signer. A filtered page may
+    /// hold fewer than `limit` documents before the end.
+    pub fn documents_for_signer(
+        e: Env,
+        signer: Address,
+        status_filter: Option<SignatureStatus>,
+        cursor: u32,
+        limit: u32,
+    ) -> DocumentPage {
+        Self::indexed_page(
+            &e,
+            read_signer_document_count(&e, signer.clone()),
+            cursor,
+            limit,
+            |index| read_signer_document(&e, signer.clone(), index),
+            |token_id, document| match &status_filter {
+                Some(status) => {
+                    read_signature(&e, token_id, signer.clone()).as_ref() == Some(status)
+                        && (*status != SignatureStatus::Waiting
+                            || current_state(&e, document) == DocumentState::Open)
+                }
+                None => true,
+            },
+        )
+    }
+
+    /// Pages over positions `cursor..count` of a document enumeration,
+    /// skipping empty positions and documents `include` rejects.
+    fn indexed_page(
+        e: &Env,
+        count: u32,
+        cursor: u32,
+        limit: u32,
+        token_at: impl Fn(u32) -> Option<u32>,
+        include: impl Fn(u32, &DocumentRecord) -> bool,
+    ) -> DocumentPage {
+        let end = count.min(cursor.saturating_add(limit.min(MAX_PAGE_SIZE)));
+        let mut documents: Vec<DocumentView> = Vec::new(e);
+        let mut index = cursor;
+        while index < end {
+            if let Some(token_id) = token_at(index) {
+                if let Some(document) = read_document(e, token_id) {
+                    if include(token_id, &document) {
+                        documents.push_back(Self::document_view(e, token_id, document));
+                    }
+                }
+            }
+            index += 1;
+        }
+        DocumentPage {
+            documents,
+            next_cursor: index,
+        }
+    }
+
+    fn document_view(e: &Env, token_id: u32, document: DocumentRecord) -> DocumentView {
+        DocumentView {
+            token_id,
+            state: current_state(e, &document),
+            progress: Self::signing_progress(e, token_id, &document),
+            signers: Self::signer_views(e, token_id),
+            owner: document.owner,
+            uri: document.uri,
+            document_hash: document.document_hash,
+            deadline: document.deadline,
+            version: document.version,
+            hash_algorithm: document.hash_algorithm,
+            rejection_policy: document.rejection_policy,
+        }
+    }
+
+    /// Participants in signing order.
+    fn signer_views(e: &Env, token_id: u32) -> Vec<SignerView> {
+        let mut views: Vec<SignerView> = Vec::new(e);
+        for signer in read_signers(e, token_id).iter() {
+            if let Some(entry) = read_signer_entry(e, token_id, signer.clone()) {
+                let timestamp = match read_receipt(e, token_id, signer.clone()) {
+                    Some(receipt) => receipt.timestamp,
+                    None => 0,
+                };
+                let rejection_reason = match read_rejection(e, token_id, signer.clone()) {
+                    Some(rejection) => RejectionReason::Hash(rejection.reason_hash),
+                    None => RejectionReason::Unspecified,
+                };
+                views.push_back(SignerView {
+                    signer,
+                    status: entry.status,
+                    role: entry.role,
+                    acted_by: entry.acted_by,
+                    timestamp,
+                    rejection_reason,
+                });
+            }
+        }
+        views
+    }
+
+    pub fn get_document_view(e: Env, token_id: u32) -> DocumentView {
+        let document = match read_document(&e, token_id) {
+            Some(document) => document,
+            None => panic_with_error!(&e, Error::TokenDoesNotExist),
+        };
+        bump_document(&e, token_id);
+        Self::document_view(&e, token_id, document)
+    }
+
+    /// Views of the given documents in the order requested. Each view costs
+    /// as much as on a page, so batches share the `MAX_PAGE_SIZE` cap.
+    pub fn get_document_views(e: Env, token_ids: Vec<u32>) -> Vec<DocumentView> {
+        if token_ids.len() > MAX_PAGE_SIZE {
+            panic_with_error!(&e, Error::TooManyDocuments)
+        }
+        let mut views: Vec<DocumentView> = Vec::new(&e);
+        for token_id in token_ids.iter() {
+            match read_document(&e, token_id) {
+                Some(document) => views.push_back(Self::document_view(&e, token_id, document)),
+                None => panic_with_error!(&e, Error::TokenDoesNotExist),
+            }
+        }
+        views
+    }
+
+    /// Every document in mint order, as long as they fit in one page.
+    fn token_ids(e: &Env) -> Vec<u32> {
+        let count = read_token_count(e);
+        if count > MAX_PAGE_SIZE {
+            panic_with_error!(e, Error::TooManyDocuments)
+        }
+        let mut token_ids: Vec<u32> = Vec::new(e);
+        for index in 0..count {
+            if let Some(token_id) = read_token_by_index(e, index) {
+                token_ids.push_back(token_id);
+            }
+        }
+        token_ids
+    }
+
+    pub fn add_extra_signers(e: Env, signers: Vec<Address>, doc_id: u32) -> Vec<Address> {
+        if signers.is_empty() {
+            panic_with_error!(&e, Error::SignersListEmpty)
+        }
+
+        Self::require_pending_owner(&e, doc_id);
+
+        let mut current_signers = read_signers(&e, doc_id);
+        for signer in signers.iter() {
+            if current_signers.contains(&signer) {
+                panic_with_error!(&e, Error::SignerAlreadyExists)
+            }
+            add_signer_entry(&e, doc_id, signer.clone(), SignerRole::Signer);
+            index_signer(&e, signer.clone(), doc_id);
+            current_signers.push_back(signer.clone());
+            event::signer_added(&e, doc_id, signer, SignerRole::Signer);
+        }
+        write_signers(&e, doc_id, &current_signers);
+        bump_document(&e, doc_id);
+
+        current_signers
+    }
+
+    pub fn remove_signer(e: Env, doc_id: u32, signer: Address) -> Vec<Address> {
+        let mut document = Self::require_pending_owner(&e, doc_id);
+        Self::require_waiting_signer(&e, doc_id, signer.clone());
+
+        let entries = read_signer_entries(&e, doc_id);
+        for entry in entries.values().iter() {
+            if entry.role == SignerRole::Witness(signer.clone()) {
+                panic_with_error!(&e, Error::InvalidRole)
+            }
+        }
+        let mut remaining_required = count_required(&entries);
+        if entries.get_unchecked(signer.clone()).role != SignerRole::Viewer {
+            remaining_required -= 1;
+        }
+        if remaining_required == 0 {
+            panic_with_error!(&e, Error::SignersListEmpty)
+        }
+        if document.threshold > remaining_required {
+            panic_with_error!(&e, Error::InvalidThreshold)
+        }
+
+        let mut current_signers = read_signers(&e, doc_id);
+        let index = current_signers.first_index_of(&signer).unwrap();
+        current_signers.remove(index);
+        remove_signature(&e, doc_id, signer.clone());
+        unindex_signer(&e, signer.clone(), doc_id);
+        write_signers(&e, doc_id, &current_signers);
+        Self::update_state(&e, doc_id, &mut document);
+        bump_document(&e, doc_id);
+
+        event::signer_removed(&e, doc_id, signer);
+        current_signers
+    }
+
+    pub fn replace_signer(
+        e: Env,
+        doc_id: u32,
+        signer: Address,
+        new_signer: Address,
+    ) -> Vec<Address> {
+        Self::require_pending_owner(&e, doc_id);
+        Self::require_waiting_signer(&e, doc_id, signer.clone());
+
+        let mut current_signers = read_signers(&e, doc_id);
+        if current_signers.contains(&new_signer) {
+            panic_with_error!(&e, Error::SignerAlreadyExists)
+        }
+        let index = current_signers.first_index_of(&signer).unwrap();
+        current_signers.set(index, new_signer.clone());
+
+        for (participant, mut entry) in read_signer_entries(&e, doc_id).iter() {
+            if entry.role == SignerRole::Witness(signer.clone()) {
+                entry.role = SignerRole::Witness(new_signer.clone());
+                write_signer_entry(&e, doc_id, participant, &entry);
+            }
+        }
+        let entry = read_signer_entry(&e, doc_id, signer.clone()).unwrap();
+        remove_signature(&e, doc_id, signer.clone());
+        write_signer_entry(&e, doc_id, new_signer.clone(), &entry);
+        unindex_signer(&e, signer.clone(), doc_id);
+        index_signer(&e, new_signer.clone(), doc_id);
+        write_signers(&e, doc_id, &current_signers);
+
+        let mut replacements = read_replacements(&e, doc_id);
+        replacements.push_back(SignerReplacement {
+            previous: signer.clone(),
+            replacement: new_signer.clone(),
+            timestamp: e.ledger().timestamp(),
+        });
+        write_replacements(&e, doc_id, &replacements);
+        bump_document(&e, doc_id);
+
+        event::signer_replaced(&e, doc_id, signer, new_signer);
+        current_signers
+    }
+
+    pub fn add_delegation(
+        e: Env,
+        delegator: Address,
+        delegate: Address,
+        scope: DelegationScope,
+        expires_at: u64,
+    ) {
+        delegator.require_auth();
+        if delegator == delegate || expires_at < e.ledger().timestamp() {
+            panic_with_error!(&e, Error::InvalidDelegation)
+        }
+
+        let delegation = Delegation {
+            delegate: delegate.clone(),
+            scope: scope.clone(),
+            expires_at,
+        };
+        write_delegation(&e, delegator.clone(), &delegation);
+        event::delegation_added(&e, delegator, delegate, scope, expires_at);
+    }
+
+    pub fn revoke_delegation(e: Env, delegator: Address, delegate: Address) {
+        delegator.require_auth();
+        if !remove_delegation(&e, delegator.clone(), delegate.clone()) {
+            panic_with_error!(&e, Error::DelegationNotFound)
+        }
+        event::delegation_revoked(&e, delegator, delegate);
+    }
+
+    pub fn get_delegations(e: Env, delegator: Address) -> Vec<Delegation> {
+        let mut delegations: Vec<Delegation> = Vec::new(&e);
+        for delegate in read_delegates(&e, delegator.clone()).iter() {
+            if let Some(delegation) = read_delegation(&e, delegator.clone(), delegate) {
+                delegations.push_back(delegation);
+            }
+        }
+        delegations
+    }
+
+    pub fn get_signer_replacements(e: Env, doc_id: u32) -> Vec<SignerReplacement> {
+        read_replacements(&e, doc_id)
+    }
+
+    pub fn get_signature_receipt(
+        e: Env,
+        token_id: u32,
+        signer: Address,
+    ) -> Option<SignatureReceipt> {
+        read_receipt(&e, token_id, signer)
+    }
+
+    /// Signatures `signer` withdrew from the document, oldest first, each with
+    /// the receipt it replaced.
+    pub fn get_revocations(e: Env, token_id: u32, signer: Address) -> Vec<SignatureRevocation> {
+        read_revocations(&e, token_id, signer)
+    }
+
+    pub fn get_rejections(e: Env, doc_id: u32) -> Map<Address, Rejection> {
+        read_rejections(&e, doc_id)
+    }
+
+    fn require_pending_owner(e: &Env, doc_id: u32) -> DocumentRecord {
+        let document = match read_document(e, doc_id) {
+            Some(document) => document,
+            None => panic_with_error!(e, Error::TokenDoesNotExist),
+        };
+        document.owner.require_auth();
+
+        Self::require_open(e, &document);
         document
     }
 
-    // pub fn add_extra_signers(e: Env, signers: Vec<Address>, doc_id: u32) {
+    fn require_open(e: &Env, document: &DocumentRecord) {
+        match current_state(e, document) {
+            DocumentState::Open => {}
+            DocumentState::Completed => panic_with_error!(e, Error::DocumentAlreadyCompleted),
+            DocumentState::Rejected => panic_with_error!(e, Error::DocumentRejected),
+            DocumentState::Expired => panic_with_error!(e, Error::DeadlinePassed),
+            DocumentState::Cancelled => panic_with_error!(e, Error::DocumentCancelled),
+            DocumentState::Voided => panic_with_error!(e, Error::DocumentVoided),
+        }
+    }
 
-    //     if signers.is_empty() {
-    //         panic_with_error!(&e, Error::SignersListEmpty)
-    //     }
+    fn update_state(e: &Env, token_id: u32, document: &mut DocumentRecord) {
+        let state = resolve_state(e, token_id, document.threshold, document.rejection_policy);
+        if state != document.state {
+            Self::set_state(e, token_id, document, state);
+        }
+    }
 
-    //     let mut doc_signings: Map<u32, Map<Address, SignatureStatus>> = e
-    //     .storage()
-    //     .persistent()
-    //     .get(&DOCSIGN)
-    //     .unwrap_or(Map::new(&e));
-    //     if doc_signings.is_empty() {
-    //         panic_with_error!(&e, Error::DocumentSigningsIsEmpty)
-    //     }
+    fn set_state(e: &Env, token_id: u32, document: &mut DocumentRecord, state: DocumentState) {
+        document.state = state;
+        write_document(e, token_id, document);
+
+        let entries = read_signer_entries(e, token_id);
+        match state {
+            DocumentState::Completed => event::completed(
+                e,
+                token_id,
+                count_signed(&entries),
+                required_signatures(document.threshold, count_required(&entries)),
+            ),
+            DocumentState::Rejected => {
+                event::document_rejected(e, token_id, count_rejected(&entries))
+            }
+            DocumentState::Expired => event::expired(e, token_id, document.deadline),
+            _ => {}
+        }
+    }
+
+    /// Persists the expiry of an open document whose deadline has passed so
+    /// that it is recorded and announced. Anyone may call it; until someone
+    /// does, no `expired` event is published for the document.
+    pub fn expire_document(e: Env, doc_id: u32) -> DocumentState {
+        let mut document = match read_document(&e, doc_id) {
+            Some(document) => document,
+            None => panic_with_error!(&e, Error::TokenDoesNotExist),
+        };
+        if document.state != DocumentState::Open
+            || current_state(&e, &document) != DocumentState::Expired
+        {
+            panic_with_error!(&e, Error::InvalidStateTransition)
+        }
 
-    //     let mut current_signers: Map<Address, SignatureStatus> = doc_signings.get(doc_id).unwrap_or(Map::new(&e));
-    //     if current_signers.is_empty() {
-    //         panic_with_error!(&e, Error::SignersListEmpty)
-    //     }
+        Self::set_state(&e, doc_id, &mut document, DocumentState::Expired);
+        bump_document(&e, doc_id);
+        DocumentState::Expired
+    }
 
-    //     for (signer) in signers.iter() {
-    //         let is_signer: SignatureStatus = current_signers.get(signer).unwrap_or_else(pa)
+    pub fn cancel_document(e: Env, doc_id: u32, reason_hash: BytesN<32>) -> DocumentState {
+        let mut document = Self::require_pending_owner(&e, doc_id);
+        let owner = document.owner.clone();
+
+        Self::close_document(
+            &e,
+            doc_id,
+            &mut document,
+            DocumentState::Cancelled,
+            owner.clone(),
+            reason_hash.clone(),
+        );
+        bump_document(&e, doc_id);
+        event::cancelled(&e, doc_id, owner, reason_hash);
+        DocumentState::Cancelled
+    }
 
-    //     }
+    /// Voids a completed document, either on the admin's authority or, when
+    /// `caller` is the owner, with the consent of the owner and every signer.
+    pub fn void_document(
+        e: Env,
+        caller: Address,
+        doc_id: u32,
+        reason_hash: BytesN<32>,
+    ) -> DocumentState {
+        let mut document = match read_document(&e, doc_id) {
+            Some(document) => document,
+            None => panic_with_error!(&e, Error::TokenDoesNotExist),
+        };
 
-    // }
+        if has_administrator(&e) && caller == read_administrator(&e) {
+            caller.require_auth();
+        } else if caller == document.owner {
+            caller.require_auth();
+            for signer in read_signers(&e, doc_id).iter() {
+                if signer != caller {
+                    signer.require_auth();
+                }
+            }
+        } else {
+            panic_with_error!(&e, Error::NotAuthorizedToVoid)
+        }
+
+        if current_state(&e, &document) != DocumentState::Completed {
+            panic_with_error!(&e, Error::InvalidStateTransition)
+        }
+
+        Self::close_document(
+            &e,
+            doc_id,
+            &mut document,
+            DocumentState::Voided,
+            caller.clone(),
+            reason_hash.clone(),
+        );
+        bump_document(&e, doc_id);
+        event::voided(&e, doc_id, caller, reason_hash);
+        DocumentState::Voided
+    }
+
+    /// Replaces the document with a new version linked to the previous hash.
+    /// The superseded version is archived with its signatures and every
+    /// participant has to act again on the new one. The new hash is described
+    /// by its own algorithm and file count, validated as on mint.
+    pub fn amend_document(
+        e: Env,
+        token_id: u32,
+        new_hash: BytesN<32>,
+        new_uri: String,
+        hash_algorithm: HashAlgorithm,
+        file_count: u32,
+    ) -> u32 {
+        let mut document = match read_document(&e, token_id) {
+            Some(document) => document,
+            None => panic_with_error!(&e, Error::TokenDoesNotExist),
+        };
+        document.owner.require_auth();
+
+        match current_state(&e, &document) {
+            DocumentState::Open | DocumentState::Completed | DocumentState::Rejected => {}
+            DocumentState::Expired => panic_with_error!(&e, Error::DeadlinePassed),
+            DocumentState::Cancelled => panic_with_error!(&e, Error::DocumentCancelled),
+            DocumentState::Voided => panic_with_error!(&e, Error::DocumentVoided),
+        }
+        // Reopening a settled document past its deadline would leave the new
+        // version expired before anyone could act on it.
+        if e.ledger().timestamp() > document.deadline {
+            panic_with_error!(&e, Error::DeadlinePassed)
+        }
+        if new_hash == document.document_hash {
+            panic_with_error!(&e, Error::InvalidAmendment)
+        }
+        Self::require_valid_envelope(&e, hash_algorithm, file_count);
+        Self::require_unique_hash(&e, new_hash.clone());
+        index_hash(&e, new_hash.clone(), token_id);
+
+        write_version(&e, token_id, &snapshot_version(&e, token_id, &document));
+        for (signer, entry) in read_signer_entries(&e, token_id).iter() {
+            remove_receipt(&e, token_id, signer.clone());
+            remove_rejection(&e, token_id, signer.clone());
+            add_signer_entry(&e, token_id, signer, entry.role);
+        }
+
+        document.previous_hash = document.document_hash.clone();
+        document.document_hash = new_hash;
+        document.uri = new_uri;
+        document.hash_algorithm = hash_algorithm;
+        document.file_count = file_count;
+        document.version += 1;
+        document.state = DocumentState::Open;
+        write_document(&e, token_id, &document);
+        bump_document(&e, token_id);
+
+        event::amended(
+            &e,
+            token_id,
+            document.version,
+            document.document_hash,
+            document.previous_hash,
+            document.uri,
+            document.hash_algorithm,
+            document.file_count,
+        );
+        document.version
+    }
+
+    /// Every version of a document, oldest first, ending with the current one.
+    pub fn get_document_versions(e: Env, token_id: u32) -> Vec<DocumentVersion> {
+        let document = match read_document(&e, token_id) {
+            Some(document) => document,
+            None => panic_with_error!(&e, Error::TokenDoesNotExist),
+        };
+
+        bump_document(&e, token_id);
+
+        let mut versions: Vec<DocumentVersion> = Vec::new(&e);
+        for version in 1..document.version {
+            if let Some(archived) = read_version(&e, token_id, version) {
+                versions.push_back(archived);
+            }
+        }
+        versions.push_back(snapshot_version(&e, token_id, &document));
+        versions
+    }
+
+    pub fn get_document_closure(e: Env, doc_id: u32) -> Option<DocumentClosure> {
+        read_closure(&e, doc_id)
+    }
+
+    fn close_document(
+        e: &Env,
+        token_id: u32,
+        document: &mut DocumentRecord,
+        state: DocumentState,
+        closed_by: Address,
+        reason_hash: BytesN<32>,
+    ) {
+        write_closure(
+            e,
+            token_id,
+            &DocumentClosure {
+                state,
+                closed_by,
+                reason_hash,
+                timestamp: e.ledger().timestamp(),
+            },
+        );
+        Self::set_state(e, token_id, document, state);
+    }
+
+    pub fn get_signing_progress(e: Env, doc_id: u32) -> SigningProgress {
+        let document = match read_document(&e, doc_id) {
+            Some(document) => document,
+            None => panic_with_error!(&e, Error::TokenDoesNotExist),
+        };
+        bump_document(&e, doc_id);
+        Self::signing_progress(&e, doc_id, &document)
+    }
+
+    fn signing_progress(e: &Env, doc_id: u32, document: &DocumentRecord) -> SigningProgress {
+        let entries = read_signer_entries(e, doc_id);
+        SigningProgress {
+            threshold: required_signatures(document.threshold, count_required(&entries)),
+            signed: count_signed(&entries),
+            signers: count_required(&entries),
+        }
+    }
+
+    /// Checks that `leaf`, the sha256 hash of one file, belongs to the
+    /// document's envelope. For `Sha256Merkle` documents `document_hash`
+    /// holds the Merkle root over the envelope's file hashes; a `Sha256`
+    /// document holds the file hash itself and takes an empty proof.
+    pub fn verify_file_in_document(
+        e: Env,
+        token_id: u32,
+        leaf: BytesN<32>,
+        proof: Vec<BytesN<32>>,
+    ) -> bool {
+        let document = match read_document(&e, token_id) {
+            Some(document) => document,
+            None => panic_with_error!(&e, Error::TokenDoesNotExist),
+        };
+        match document.hash_algorithm {
+            HashAlgorithm::Sha256 => proof.is_empty() && leaf == document.document_hash,
+            HashAlgorithm::Sha256Merkle => {
+                proof.len() <= tree_depth(document.file_count)
+                    && compute_root(&e, leaf, &proof) == document.document_hash
+            }
+            HashAlgorithm::LegacyText => false,
+        }
+    }
+
+    /// Recomputes the sha256 hash of `content` and compares it with the hash
+    /// registered for a single file document.
+    pub fn verify_document(e: Env, token_id: u32, content: Bytes) -> bool {
+        let document = match read_document(&e, token_id) {
+            Some(document) => document,
+            None => panic_with_error!(&e, Error::TokenDoesNotExist),
+        };
+        if document.hash_algorithm != HashAlgorithm::Sha256 {
+            panic_with_error!(&e, Error::InvalidHashAlgorithm)
+        }
+
+        e.crypto().sha256(&content) == document.document_hash
+    }
+
+    pub fn get_document_state(e: Env, doc_id: u32) -> DocumentState {
+        let document = match read_document(&e, doc_id) {
+            Some(document) => document,
+            None => panic_with_error!(&e, Error::TokenDoesNotExist),
+        };
+        bump_document(&e, doc_id);
+        current_state(&e, &document)
+    }
+
+    /// Extends the lifetime of every entry of a document. Anyone may call it,
+    /// paying the rent to keep a document from being archived.
+    pub fn extend_document_ttl(e: Env, token_id: u32) {
+        if !exists(&e, token_id) {
+            panic_with_error!(&e, Error::TokenDoesNotExist)
+        }
+        bump_document_entries(&e, token_id);
+    }
+
+    fn require_waiting_signer(e: &Env, doc_id: u32, signer: Address) {
+        match read_signature(e, doc_id, signer) {
+            Some(SignatureStatus::Waiting) | Some(SignatureStatus::NotASigner) => {}
+            Some(_) => panic_with_error!(e, Error::SignerAlreadyActed),
+            None => panic_with_error!(e, Error::SignerDoesNotExist),
+        }
+    }
 }
 
 // ------------> FUTURENET CONTRACT ID = CB6Y74MX2VRQ7C7ITKZM4SOAZOR7MQ3SX2QBJLXP63V43YCYNT46QKMG --------------------
@@ -0,0 +1,40 @@
+use soroban_sdk::{Bytes, BytesN, Env, Vec};
+
+// Leaves and internal nodes are hashed under different prefixes so that an
+// internal node can never be presented as a file hash.
+const LEAF_PREFIX: u8 = 0;
+const NODE_PREFIX: u8 = 1;
+
+/// Number of proof steps from a leaf to the root of a tree over `file_count`
+/// leaves.
+pub fn tree_depth(file_count: u32) -> u32 {
+    if file_count <= 1 {
+        0
+    } else {
+        32 - (file_count - 1).leading_zeros()
+    }
+}
+
+pub fn hash_leaf(e: &Env, leaf: &BytesN<32>) -> BytesN<32> {
+    let mut data = Bytes::from_array(e, &[LEAF_PREFIX]);
+    data.append(&Bytes::from_array(e, &leaf.to_array()));
+    e.crypto().sha256(&data)
+}
+
+/// Folds a sha256 inclusion proof into the root it commits to. Each pair is
+/// hashed in ascending order, so proofs carry no left/right position bits.
+pub fn compute_root(e: &Env, leaf: BytesN<32>, proof: &Vec<BytesN<32>>) -> BytesN<32> {
+    let mut node = hash_leaf(e, &leaf);
+    for sibling in proof.iter() {
+        let (left, right) = if node <= sibling {
+            (node, sibling)
+        } else {
+            (sibling, node)
+        };
+        let mut data = Bytes::from_array(e, &[NODE_PREFIX]);
+        data.append(&Bytes::from_array(e, &left.to_array()));
+        data.append(&Bytes::from_array(e, &right.to_array()));
+        node = e.crypto().sha256(&data);
+    }
+    node
+}
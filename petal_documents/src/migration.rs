@@ -0,0 +1,150 @@
+use soroban_sdk::{symbol_short, xdr::ToXdr, Address, BytesN, Env, Map, String, Symbol};
+
+use crate::address_index::{index_owner, index_signer};
+use crate::document::{
+    append_token, has_document, resolve_state, write_document, write_signer_entry, write_signers,
+};
+use crate::hash_index::index_hash;
+use crate::nonce::write_nonce;
+use crate::storage_types::{DataKey, DocumentRecord, SignerEntry};
+use crate::{ActedBy, HashAlgorithm, RejectionPolicy, SignatureStatus, SignerRole, SigningOrder};
+
+pub(crate) const STORAGE_VERSION: u32 = 1;
+
+// Global maps written by the first release of the contract, before documents
+// moved to per-token keys.
+const OWNERS: Symbol = symbol_short!("OWNERS");
+const URIS: Symbol = symbol_short!("URIS");
+const NONCES: Symbol = symbol_short!("NONCES");
+const T2DHASH: Symbol = symbol_short!("T2DHASH");
+const DEADLINES: Symbol = symbol_short!("DEADLINES");
+const DOCSIGN: Symbol = symbol_short!("DOCSIGN");
+
+// An ScVal::String encodes as its type tag and length before the contents.
+const STRING_XDR_HEADER_LEN: u32 = 8;
+
+// Legacy document hashes were free-form text; they are carried over as the
+// sha256 of that text. The text is read through its XDR encoding so strings
+// of any length are handled.
+fn legacy_hash(e: &Env, text: &String) -> BytesN<32> {
+    let xdr = text.clone().to_xdr(e);
+    let contents = xdr.slice(STRING_XDR_HEADER_LEN..STRING_XDR_HEADER_LEN + text.len());
+    e.crypto().sha256(&contents)
+}
+
+pub fn read_storage_version(e: &Env) -> u32 {
+    let key = DataKey::StorageVersion;
+    e.storage().instance().get(&key).unwrap_or(0)
+}
+
+pub fn write_storage_version(e: &Env, version: u32) {
+    let key = DataKey::StorageVersion;
+    e.storage().instance().set(&key, &version);
+}
+
+pub fn has_legacy_storage(e: &Env) -> bool {
+    e.storage().persistent().has(&OWNERS) || e.storage().persistent().has(&NONCES)
+}
+
+/// Moves up to `batch_size` legacy records into the keyed layout, starting at
+/// the stored cursor. Documents are migrated first, then signer nonces.
+/// Returns the number of records left; once it reaches zero the legacy maps
+/// are removed and the storage version is bumped.
+pub fn migrate_legacy_storage(e: &Env, batch_size: u32) -> u32 {
+    let owners: Map<u32, Address> = e.storage().persistent().get(&OWNERS).unwrap_or(Map::new(e));
+    let nonces: Map<Address, u32> = e.storage().persistent().get(&NONCES).unwrap_or(Map::new(e));
+    let token_ids = owners.keys();
+    let nonce_holders = nonces.keys();
+    let total = token_ids.len() + nonce_holders.len();
+
+    let mut cursor: u32 = e
+        .storage()
+        .instance()
+        .get(&DataKey::MigrationCursor)
+        .unwrap_or(0);
+    let end = total.min(cursor.saturating_add(batch_size));
+
+    if cursor < token_ids.len() {
+        let uris: Map<u32, String> = e.storage().persistent().get(&URIS).unwrap_or(Map::new(e));
+        let hashes: Map<u32, String> = e
+            .storage()
+            .persistent()
+            .get(&T2DHASH)
+            .unwrap_or(Map::new(e));
+        let deadlines: Map<u32, u64> = e
+            .storage()
+            .persistent()
+            .get(&DEADLINES)
+            .unwrap_or(Map::new(e));
+        let doc_signings: Map<u32, Map<Address, SignatureStatus>> = e
+            .storage()
+            .persistent()
+            .get(&DOCSIGN)
+            .unwrap_or(Map::new(e));
+
+        while cursor < end && cursor < token_ids.len() {
+            let token_id = token_ids.get_unchecked(cursor);
+            if !has_document(e, token_id) {
+                let signings = doc_signings.get(token_id).unwrap_or(Map::new(e));
+                for (signer, status) in signings.iter() {
+                    let role = if status == SignatureStatus::NotASigner {
+                        SignerRole::Viewer
+                    } else {
+                        SignerRole::Signer
+                    };
+                    let entry = SignerEntry {
+                        status,
+                        role,
+                        acted_by: ActedBy::Signer,
+                    };
+                    index_signer(e, signer.clone(), token_id);
+                    write_signer_entry(e, token_id, signer, &entry);
+                }
+                write_signers(e, token_id, &signings.keys());
+
+                let document = DocumentRecord {
+                    owner: owners.get_unchecked(token_id),
+                    uri: uris.get(token_id).unwrap_or(String::from_slice(e, "")),
+                    document_hash: legacy_hash(
+                        e,
+                        &hashes.get(token_id).unwrap_or(String::from_slice(e, "")),
+                    ),
+                    deadline: deadlines.get(token_id).unwrap_or(0),
+                    state: resolve_state(e, token_id, 0, RejectionPolicy::Terminate),
+                    signing_order: SigningOrder::Parallel,
+                    threshold: 0,
+                    rejection_policy: RejectionPolicy::Terminate,
+                    version: 1,
+                    previous_hash: BytesN::from_array(e, &[0; 32]),
+                    hash_algorithm: HashAlgorithm::LegacyText,
+                    file_count: 1,
+                };
+                write_document(e, token_id, &document);
+                append_token(e, token_id);
+                index_hash(e, document.document_hash, token_id);
+                index_owner(e, document.owner, token_id);
+            }
+            cursor += 1;
+        }
+    }
+
+    while cursor < end {
+        let holder = nonce_holders.get_unchecked(cursor - token_ids.len());
+        write_nonce(e, holder.clone(), nonces.get_unchecked(holder));
+        cursor += 1;
+    }
+
+    if cursor == total {
+        for key in [OWNERS, URIS, NONCES, T2DHASH, DEADLINES, DOCSIGN].iter() {
+            e.storage().persistent().remove(key);
+        }
+        e.storage().instance().remove(&DataKey::MigrationCursor);
+        write_storage_version(e, STORAGE_VERSION);
+    } else {
+        e.storage()
+            .instance()
+            .set(&DataKey::MigrationCursor, &cursor);
+    }
+
+    total - cursor
+}
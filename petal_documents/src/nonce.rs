@@ -0,0 +1,21 @@
+use soroban_sdk::{Address, Env};
+
+use crate::storage_types::DataKey;
+use crate::ttl::bump_persistent;
+
+pub fn read_nonce(e: &Env, id: Address) -> u32 {
+    let key = DataKey::Nonce(id);
+    e.storage().persistent().get(&key).unwrap_or(0)
+}
+
+pub fn increment_nonce(e: &Env, id: Address) -> u32 {
+    let nonce = read_nonce(e, id.clone());
+    write_nonce(e, id, nonce + 1);
+    nonce
+}
+
+pub fn write_nonce(e: &Env, id: Address, nonce: u32) {
+    let key = DataKey::Nonce(id);
+    e.storage().persistent().set(&key, &nonce);
+    bump_persistent(e, &key);
+}
@@ -0,0 +1,24 @@
+use soroban_sdk::{
+    xdr::{FromXdr, ToXdr},
+    Address, Bytes, BytesN, Env,
+};
+
+// XDR header of ScVal::Address(ScAddress::Account(PublicKey::Ed25519(..))),
+// followed by the raw 32 byte key.
+const ACCOUNT_ADDRESS_XDR_PREFIX: [u8; 12] = [0, 0, 0, 18, 0, 0, 0, 0, 0, 0, 0, 0];
+
+/// Bytes a signer signs for `message`: the network id and this contract's
+/// address followed by the XDR encoded `SignedMessage`, so a signature can't
+/// be replayed against another deployment or network.
+pub fn signature_payload(e: &Env, message: &Bytes) -> Bytes {
+    let mut payload: Bytes = e.ledger().network_id().into();
+    payload.append(&e.current_contract_address().to_xdr(e));
+    payload.append(message);
+    payload
+}
+
+pub fn account_address(e: &Env, public_key: &BytesN<32>) -> Address {
+    let mut xdr = Bytes::from_array(e, &ACCOUNT_ADDRESS_XDR_PREFIX);
+    xdr.append(&public_key.clone().into());
+    Address::from_xdr(e, &xdr).expect("public key is not a valid account address")
+}
@@ -1,30 +1,238 @@
-use soroban_sdk::{contracttype, Address};
+use soroban_sdk::{contracttype, Address, BytesN, Map, String, Vec};
+
+use crate::event::RejectionReason;
+use crate::{
+    ActedBy, DelegationScope, DocumentState, HashAlgorithm, RejectionPolicy, SignatureStatus,
+    SignerRole, SigningOrder,
+};
 
 pub(crate) const INSTANCE_BUMP_AMOUNT_HIGH_WATERMARK: u32 = 34560; // 2 days
 pub(crate) const INSTANCE_BUMP_AMOUNT_LOW_WATERMARK: u32 = 17280; // 1 day
 
-pub(crate) const BALANCE_BUMP_AMOUNT_HIGH_WATERMARK: u32 = 518400; // 30 days
-pub(crate) const BALANCE_BUMP_AMOUNT_LOW_WATERMARK: u32 = 259200; // 15 days
+pub(crate) const DOCUMENT_BUMP_AMOUNT_HIGH_WATERMARK: u32 = 518400; // 30 days
+pub(crate) const DOCUMENT_BUMP_AMOUNT_LOW_WATERMARK: u32 = 259200; // 15 days
+
+#[derive(Clone)]
+#[contracttype]
+pub struct SignatureDataKey {
+    pub token_id: u32,
+    pub signer: Address,
+}
+
+#[derive(Clone)]
+#[contracttype]
+pub struct AddressIndexKey {
+    pub address: Address,
+    pub index: u32,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[contracttype]
+pub struct DocumentRecord {
+    pub owner: Address,
+    pub uri: String,
+    pub document_hash: BytesN<32>,
+    pub deadline: u64,
+    pub state: DocumentState,
+    pub signing_order: SigningOrder,
+    pub threshold: u32,
+    pub rejection_policy: RejectionPolicy,
+    pub version: u32,
+    pub previous_hash: BytesN<32>,
+    pub hash_algorithm: HashAlgorithm,
+    pub file_count: u32,
+}
+
+#[derive(Clone)]
+#[contracttype]
+pub struct VersionDataKey {
+    pub token_id: u32,
+    pub version: u32,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[contracttype]
+pub struct DocumentVersion {
+    pub version: u32,
+    pub uri: String,
+    pub document_hash: BytesN<32>,
+    pub previous_hash: BytesN<32>,
+    pub hash_algorithm: HashAlgorithm,
+    pub file_count: u32,
+    pub state: DocumentState,
+    pub signatures: Map<Address, SignerEntry>,
+    pub receipts: Map<Address, SignatureReceipt>,
+    pub rejections: Map<Address, Rejection>,
+}
 
 #[derive(Clone)]
 #[contracttype]
-pub struct AllowanceDataKey {
-    pub from: Address,
-    pub spender: Address,
+pub struct DelegationDataKey {
+    pub delegator: Address,
+    pub delegate: Address,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[contracttype]
+pub struct Delegation {
+    pub delegate: Address,
+    pub scope: DelegationScope,
+    pub expires_at: u64,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[contracttype]
+pub struct SignerEntry {
+    pub status: SignatureStatus,
+    pub role: SignerRole,
+    pub acted_by: ActedBy,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[contracttype]
+pub struct SignatureReceipt {
+    pub status: SignatureStatus,
+    pub document_hash: BytesN<32>,
+    pub nonce: u32,
+    pub timestamp: u64,
+    pub sequence: u32,
+    pub submitter: Address,
+}
+
+/// The receipt a revocation withdrew. Signatures carried over from legacy
+/// storage were never given one.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[contracttype]
+pub enum RevokedReceipt {
+    Legacy,
+    Receipt(SignatureReceipt),
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[contracttype]
+pub struct SignatureRevocation {
+    pub revoked: RevokedReceipt,
+    pub nonce: u32,
+    pub timestamp: u64,
+    pub sequence: u32,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[contracttype]
+pub struct Rejection {
+    pub reason_hash: BytesN<32>,
+    pub timestamp: u64,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[contracttype]
+pub struct SignerReplacement {
+    pub previous: Address,
+    pub replacement: Address,
+    pub timestamp: u64,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[contracttype]
+pub struct DocumentClosure {
+    pub state: DocumentState,
+    pub closed_by: Address,
+    pub reason_hash: BytesN<32>,
+    pub timestamp: u64,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[contracttype]
+pub struct SigningProgress {
+    pub threshold: u32,
+    pub signed: u32,
+    pub signers: u32,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[contracttype]
+pub struct DocumentMatch {
+    pub token_id: u32,
+    pub state: DocumentState,
+    pub current: bool,
+    pub progress: SigningProgress,
 }
 
+/// A participant as shown in a `DocumentView`. `timestamp` is when the
+/// signer last signed or rejected, zero while they have not acted;
+/// `rejection_reason` is only set for signers who rejected with a reason.
+#[derive(Clone, Debug, PartialEq, Eq)]
 #[contracttype]
-pub struct AllowanceValue {
+pub struct SignerView {
+    pub signer: Address,
+    pub status: SignatureStatus,
+    pub role: SignerRole,
+    pub acted_by: ActedBy,
+    pub timestamp: u64,
+    pub rejection_reason: RejectionReason,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[contracttype]
+pub struct DocumentView {
+    pub token_id: u32,
+    pub owner: Address,
+    pub uri: String,
+    pub document_hash: BytesN<32>,
+    pub deadline: u64,
+    pub state: DocumentState,
+    pub version: u32,
+    pub hash_algorithm: HashAlgorithm,
+    pub rejection_policy: RejectionPolicy,
+    pub signers: Vec<SignerView>,
+    pub progress: SigningProgress,
+}
+
+/// A page of documents in mint order. `next_cursor` is where the following
+/// page starts; documents minted later are picked up from there.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[contracttype]
+pub struct DocumentPage {
+    pub documents: Vec<DocumentView>,
+    pub next_cursor: u32,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[contracttype]
+pub struct CreationFee {
+    pub token: Address,
     pub amount: i128,
-    pub expiration_ledger: u32,
 }
 
 #[derive(Clone)]
 #[contracttype]
 pub enum DataKey {
-    Allowance(AllowanceDataKey),
-    Balance(Address),
-    Nonce(Address),
-    State(Address),
     Admin,
+    StorageVersion,
+    MigrationCursor,
+    CreationFee,
+    UniqueHashes,
+    LastTokenId,
+    Nonce(Address),
+    Document(u32),
+    Signers(u32),
+    Signature(SignatureDataKey),
+    Rejection(SignatureDataKey),
+    Revocations(SignatureDataKey),
+    Receipt(SignatureDataKey),
+    Replacements(u32),
+    Closure(u32),
+    Version(VersionDataKey),
+    Delegation(DelegationDataKey),
+    Delegates(Address),
+    TokensByHash(BytesN<32>),
+    TokenCount,
+    TokenByIndex(u32),
+    TokenIndex(u32),
+    OwnerDocumentCount(Address),
+    OwnerDocument(AddressIndexKey),
+    OwnerDocumentIndex(u32),
+    SignerDocumentCount(Address),
+    SignerDocument(AddressIndexKey),
+    SignerDocumentIndex(SignatureDataKey),
 }
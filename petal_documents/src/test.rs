@@ -0,0 +1,2237 @@
+#![cfg(test)]
+extern crate std;
+
+use crate::event::{
+    Completed, DocumentCreated, DocumentRejected, Expired, Rejected, RejectionReason, Signed,
+    SignerAdded,
+};
+use crate::signature::account_address;
+use crate::storage_types::{
+    CreationFee, DataKey, Delegation, DocumentClosure, DocumentMatch, DocumentPage, DocumentRecord,
+    DocumentView, Rejection, RevokedReceipt, SignatureDataKey, SignatureReceipt,
+    SignatureRevocation, SignerEntry, SignerReplacement, SignerView, SigningProgress,
+    DOCUMENT_BUMP_AMOUNT_HIGH_WATERMARK, DOCUMENT_BUMP_AMOUNT_LOW_WATERMARK,
+    INSTANCE_BUMP_AMOUNT_LOW_WATERMARK,
+};
+use crate::{
+    ActedBy, DelegationScope, DocumentOptions, DocumentState, HashAlgorithm, PetalDocuments,
+    PetalDocumentsClient, RejectionPolicy, SignatureStatus, SignedMessage, SignerRole,
+    SigningOrder, MAX_PAGE_SIZE,
+};
+use ed25519_dalek::{Signer, SigningKey};
+use soroban_sdk::{
+    map, symbol_short,
+    testutils::{Address as _, AuthorizedFunction, AuthorizedInvocation, Events, Ledger},
+    token, vec,
+    xdr::{ContractDataDurability, LedgerKey, ScVal, ToXdr},
+    Address, Bytes, BytesN, Env, IntoVal, Map, String, Symbol, TryFromVal, Val, Vec,
+};
+
+fn signature_statuses(
+    documents: &PetalDocumentsClient,
+    token_id: &u32,
+) -> Map<Address, SignatureStatus> {
+    let mut statuses = Map::new(&documents.env);
+    for (signer, entry) in documents.get_document(token_id).iter() {
+        statuses.set(signer, entry.status);
+    }
+    statuses
+}
+
+fn create_documents<'a>(e: &Env, admin: &Address) -> PetalDocumentsClient<'a> {
+    let documents = PetalDocumentsClient::new(e, &e.register_contract(None, PetalDocuments {}));
+    documents.init(admin, &0);
+    documents
+}
+
+#[test]
+fn test_mint_and_sign() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let admin = Address::random(&e);
+    let owner = Address::random(&e);
+    let signer1 = Address::random(&e);
+    let signer2 = Address::random(&e);
+    let documents = create_documents(&e, &admin);
+
+    let hash = BytesN::from_array(&e, &[1; 32]);
+    let uri = String::from_slice(&e, "uri1");
+    documents.safe_mint(
+        &owner,
+        &owner,
+        &None,
+        &uri,
+        &vec![&e, signer1.clone(), signer2.clone(), signer1.clone()],
+        &hash,
+        &1000,
+        &None,
+    );
+
+    let document = signature_statuses(&documents, &1);
+    assert_eq!(document.len(), 2);
+    assert_eq!(
+        document.get(signer1.clone()),
+        Some(SignatureStatus::Waiting)
+    );
+    assert_eq!(
+        document.get(signer2.clone()),
+        Some(SignatureStatus::Waiting)
+    );
+    assert_eq!(documents.get_token_uri(&1), uri);
+    assert_eq!(documents.get_owners().get(1), Some(owner.clone()));
+    assert_eq!(documents.get_td_hashes().get(1), Some(hash.clone()));
+    assert_eq!(documents.get_deadlines().get(1), Some(1000));
+
+    let signings =
+        documents.sign_document(&hash, &signer1, &SignatureStatus::Signed, &1, &None, &None);
+    assert_eq!(signings.get(signer1.clone()), Some(SignatureStatus::Signed));
+    assert_eq!(
+        signings.get(signer2.clone()),
+        Some(SignatureStatus::Waiting)
+    );
+    assert_eq!(documents.get_nonces(&signer1), 1);
+    assert_eq!(documents.get_nonces(&signer2), 0);
+}
+
+#[test]
+fn test_token_ids() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let admin = Address::random(&e);
+    let owner = Address::random(&e);
+    let signer = Address::random(&e);
+    let contract_id = e.register_contract(None, PetalDocuments {});
+    let documents = PetalDocumentsClient::new(&e, &contract_id);
+    documents.init(&admin, &100);
+
+    let hash = BytesN::from_array(&e, &[1; 32]);
+    let uri = String::from_slice(&e, "uri1");
+    let signers = vec![&e, signer.clone()];
+    assert_eq!(
+        documents.safe_mint(&owner, &owner, &None, &uri, &signers, &hash, &1000, &None),
+        101
+    );
+    assert_eq!(
+        documents.safe_mint(&owner, &owner, &None, &uri, &signers, &hash, &1000, &None),
+        102
+    );
+
+    assert_eq!(
+        documents.safe_mint(
+            &admin,
+            &owner,
+            &Some(200),
+            &uri,
+            &signers,
+            &hash,
+            &1000,
+            &None
+        ),
+        200
+    );
+    assert_eq!(
+        e.auths(),
+        std::vec![(
+            admin.clone(),
+            AuthorizedInvocation {
+                function: AuthorizedFunction::Contract((
+                    contract_id.clone(),
+                    symbol_short!("safe_mint"),
+                    (
+                        admin.clone(),
+                        owner.clone(),
+                        Some(200_u32),
+                        uri.clone(),
+                        signers.clone(),
+                        hash.clone(),
+                        1000_u64,
+                        None::<DocumentOptions>,
+                    )
+                        .into_val(&e),
+                )),
+                sub_invocations: std::vec![]
+            }
+        )]
+    );
+    assert_eq!(
+        documents.safe_mint(
+            &admin,
+            &owner,
+            &Some(150),
+            &uri,
+            &signers,
+            &hash,
+            &1000,
+            &None
+        ),
+        150
+    );
+    assert_eq!(
+        documents.safe_mint(&owner, &owner, &None, &uri, &signers, &hash, &1000, &None),
+        201
+    );
+    assert_eq!(documents.get_owners().len(), 5);
+}
+
+#[test]
+fn test_documents_are_stored_per_token() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let admin = Address::random(&e);
+    let owner = Address::random(&e);
+    let signer = Address::random(&e);
+    let documents = create_documents(&e, &admin);
+
+    let hash = BytesN::from_array(&e, &[1; 32]);
+    let uri = String::from_slice(&e, "uri1");
+    documents.safe_mint(
+        &owner,
+        &owner,
+        &None,
+        &uri,
+        &vec![&e, signer.clone()],
+        &hash,
+        &1000,
+        &None,
+    );
+    documents.safe_mint(
+        &owner,
+        &owner,
+        &None,
+        &uri,
+        &vec![&e, signer.clone()],
+        &hash,
+        &1000,
+        &None,
+    );
+    documents.sign_document(&hash, &signer, &SignatureStatus::Rejected, &2, &None, &None);
+
+    e.as_contract(&documents.address, || {
+        let record: DocumentRecord = e.storage().persistent().get(&DataKey::Document(1)).unwrap();
+        assert_eq!(record.owner, owner);
+        assert_eq!(record.document_hash, hash);
+
+        let first: SignerEntry = e
+            .storage()
+            .persistent()
+            .get(&DataKey::Signature(SignatureDataKey {
+                token_id: 1,
+                signer: signer.clone(),
+            }))
+            .unwrap();
+        let second: SignerEntry = e
+            .storage()
+            .persistent()
+            .get(&DataKey::Signature(SignatureDataKey {
+                token_id: 2,
+                signer: signer.clone(),
+            }))
+            .unwrap();
+        assert_eq!(first.status, SignatureStatus::Waiting);
+        assert_eq!(second.status, SignatureStatus::Rejected);
+    });
+
+    assert_eq!(documents.get_documents().len(), 2);
+}
+
+#[test]
+fn test_migrate_legacy_storage() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let admin = Address::random(&e);
+    let owner = Address::random(&e);
+    let signer1 = Address::random(&e);
+    let signer2 = Address::random(&e);
+    let contract_id = e.register_contract(None, PetalDocuments {});
+    let documents = PetalDocumentsClient::new(&e, &contract_id);
+
+    e.as_contract(&contract_id, || {
+        let mut owners: Map<u32, Address> = Map::new(&e);
+        let mut uris: Map<u32, String> = Map::new(&e);
+        let mut hashes: Map<u32, String> = Map::new(&e);
+        let mut deadlines: Map<u32, u64> = Map::new(&e);
+        let mut doc_signings: Map<u32, Map<Address, SignatureStatus>> = Map::new(&e);
+        for token_id in 1..4u32 {
+            owners.set(token_id, owner.clone());
+            uris.set(token_id, String::from_slice(&e, "uri"));
+            hashes.set(token_id, String::from_slice(&e, "hash"));
+            deadlines.set(token_id, 1000 + token_id as u64);
+            doc_signings.set(
+                token_id,
+                map![
+                    &e,
+                    (signer1.clone(), SignatureStatus::Signed),
+                    (signer2.clone(), SignatureStatus::Waiting)
+                ],
+            );
+        }
+        // Legacy hashes were free-form text of any length.
+        hashes.set(3, String::from_slice(&e, &"a".repeat(300)));
+        let nonces: Map<Address, u32> = map![&e, (signer1.clone(), 3)];
+
+        e.storage()
+            .persistent()
+            .set(&symbol_short!("OWNERS"), &owners);
+        e.storage().persistent().set(&symbol_short!("URIS"), &uris);
+        e.storage()
+            .persistent()
+            .set(&symbol_short!("T2DHASH"), &hashes);
+        e.storage()
+            .persistent()
+            .set(&symbol_short!("DEADLINES"), &deadlines);
+        e.storage()
+            .persistent()
+            .set(&symbol_short!("DOCSIGN"), &doc_signings);
+        e.storage()
+            .persistent()
+            .set(&symbol_short!("NONCES"), &nonces);
+    });
+
+    documents.init(&admin, &0);
+    assert_eq!(documents.get_storage_version(), 0);
+
+    assert_eq!(documents.migrate_storage(&2), 2);
+    assert_eq!(
+        e.auths(),
+        std::vec![(
+            admin.clone(),
+            AuthorizedInvocation {
+                function: AuthorizedFunction::Contract((
+                    contract_id.clone(),
+                    Symbol::new(&e, "migrate_storage"),
+                    (2_u32,).into_val(&e),
+                )),
+                sub_invocations: std::vec![]
+            }
+        )]
+    );
+    assert_eq!(documents.get_storage_version(), 0);
+    assert_eq!(documents.get_documents().len(), 2);
+
+    assert_eq!(documents.migrate_storage(&2), 0);
+    assert_eq!(documents.get_storage_version(), 1);
+
+    for token_id in 1..4u32 {
+        let document = signature_statuses(&documents, &token_id);
+        assert_eq!(document.get(signer1.clone()), Some(SignatureStatus::Signed));
+        assert_eq!(
+            document.get(signer2.clone()),
+            Some(SignatureStatus::Waiting)
+        );
+        assert_eq!(documents.get_owners().get(token_id), Some(owner.clone()));
+        assert_eq!(
+            documents.get_token_uri(&token_id),
+            String::from_slice(&e, "uri")
+        );
+        let legacy_hash = if token_id == 3 {
+            "a".repeat(300)
+        } else {
+            "hash".into()
+        };
+        assert_eq!(
+            documents.get_td_hashes().get(token_id),
+            Some(
+                e.crypto()
+                    .sha256(&Bytes::from_slice(&e, legacy_hash.as_bytes()))
+            )
+        );
+        assert_eq!(
+            documents.get_deadlines().get(token_id),
+            Some(1000 + token_id as u64)
+        );
+    }
+    assert_eq!(documents.get_nonces(&signer1), 3);
+    assert_eq!(documents.get_nonces(&signer2), 0);
+
+    e.as_contract(&contract_id, || {
+        assert!(!e.storage().persistent().has(&symbol_short!("OWNERS")));
+        assert!(!e.storage().persistent().has(&symbol_short!("DOCSIGN")));
+        assert!(!e.storage().persistent().has(&symbol_short!("NONCES")));
+    });
+
+    let hash = e.crypto().sha256(&Bytes::from_slice(&e, b"hash"));
+    documents.sign_document(&hash, &signer2, &SignatureStatus::Signed, &2, &None, &None);
+    documents.safe_mint(
+        &owner,
+        &owner,
+        &None,
+        &String::from_slice(&e, "uri"),
+        &vec![&e, signer2.clone()],
+        &hash,
+        &1000,
+        &None,
+    );
+    assert_eq!(documents.get_documents().len(), 4);
+    assert_eq!(
+        documents
+            .documents_for_owner(&owner, &0, &10)
+            .documents
+            .len(),
+        4
+    );
+    assert_eq!(
+        documents
+            .documents_for_signer(&signer1, &Some(SignatureStatus::Signed), &0, &10)
+            .documents
+            .len(),
+        3
+    );
+    assert_eq!(
+        signature_statuses(&documents, &2).get(signer2.clone()),
+        Some(SignatureStatus::Signed)
+    );
+
+    // Migrated hashes can't be checked against files, so amending a
+    // migrated document moves it onto the algorithm the owner supplies.
+    assert!(!documents.verify_file_in_document(&2, &hash, &vec![&e]));
+    let new_hash = BytesN::from_array(&e, &[9; 32]);
+    documents.amend_document(
+        &2,
+        &new_hash,
+        &String::from_slice(&e, "uri2"),
+        &HashAlgorithm::Sha256,
+        &1,
+    );
+    assert_eq!(
+        documents.get_document_view(&2).hash_algorithm,
+        HashAlgorithm::Sha256
+    );
+    assert_eq!(
+        documents
+            .get_document_versions(&2)
+            .get_unchecked(0)
+            .hash_algorithm,
+        HashAlgorithm::LegacyText
+    );
+    assert!(documents.verify_file_in_document(&2, &new_hash, &vec![&e]));
+}
+
+#[test]
+fn test_sign_document_with_signature() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let admin = Address::random(&e);
+    let owner = Address::random(&e);
+    let documents = create_documents(&e, &admin);
+
+    let signing_key = SigningKey::from_bytes(&[7; 32]);
+    let public_key = BytesN::from_array(&e, &signing_key.verifying_key().to_bytes());
+    let signer = account_address(&e, &public_key);
+
+    let hash = BytesN::from_array(&e, &[1; 32]);
+    let uri = String::from_slice(&e, "uri1");
+    documents.safe_mint(
+        &owner,
+        &owner,
+        &None,
+        &uri,
+        &vec![&e, signer.clone()],
+        &hash,
+        &1000,
+        &None,
+    );
+
+    let message = SignedMessage {
+        deadline: 500,
+        description: String::from_slice(&e, "employment contract"),
+        document_hash: hash.clone(),
+        document_uri: uri.clone(),
+        signer: signer.clone(),
+        status: SignatureStatus::Signed,
+        token_id: 1,
+        nonce: documents.get_nonces(&signer),
+    }
+    .to_xdr(&e);
+    let mut payload: Bytes = e.ledger().network_id().into();
+    payload.append(&documents.address.clone().to_xdr(&e));
+    payload.append(&message);
+    let mut payload_bytes = std::vec![0u8; payload.len() as usize];
+    payload.copy_into_slice(&mut payload_bytes);
+    let signature = BytesN::from_array(&e, &signing_key.sign(&payload_bytes).to_bytes());
+
+    let signings = documents.sign_document_with_signature(&message, &public_key, &signature, &None);
+    assert_eq!(e.auths(), std::vec![]);
+    assert_eq!(signings.get(signer.clone()), Some(SignatureStatus::Signed));
+    assert_eq!(documents.get_nonces(&signer), 1);
+    assert_eq!(
+        documents
+            .get_signature_receipt(&1, &signer)
+            .map(|receipt| receipt.submitter),
+        Some(signer.clone())
+    );
+}
+
+#[test]
+fn test_creation_fee() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let admin = Address::random(&e);
+    let owner = Address::random(&e);
+    let signer = Address::random(&e);
+    let treasury = Address::random(&e);
+    let documents = create_documents(&e, &admin);
+
+    let fee_token = e.register_stellar_asset_contract(admin.clone());
+    let fee_token_client = token::Client::new(&e, &fee_token);
+    token::StellarAssetClient::new(&e, &fee_token).mint(&owner, &1000);
+
+    documents.set_creation_fee(&fee_token, &150);
+    assert_eq!(
+        documents.get_creation_fee(),
+        Some(CreationFee {
+            token: fee_token.clone(),
+            amount: 150
+        })
+    );
+
+    let hash = BytesN::from_array(&e, &[1; 32]);
+    let uri = String::from_slice(&e, "uri1");
+    documents.safe_mint(
+        &owner,
+        &owner,
+        &None,
+        &uri,
+        &vec![&e, signer.clone()],
+        &hash,
+        &1000,
+        &None,
+    );
+    assert_eq!(fee_token_client.balance(&owner), 850);
+    assert_eq!(fee_token_client.balance(&documents.address), 150);
+
+    documents.safe_mint(
+        &owner,
+        &admin,
+        &None,
+        &uri,
+        &vec![&e, signer.clone()],
+        &hash,
+        &1000,
+        &None,
+    );
+    assert_eq!(fee_token_client.balance(&owner), 700);
+    assert_eq!(fee_token_client.balance(&documents.address), 300);
+
+    documents.safe_mint(
+        &admin,
+        &owner,
+        &None,
+        &uri,
+        &vec![&e, signer.clone()],
+        &hash,
+        &1000,
+        &None,
+    );
+    assert_eq!(fee_token_client.balance(&owner), 700);
+    assert_eq!(fee_token_client.balance(&documents.address), 300);
+
+    assert_eq!(documents.withdraw_fees(&fee_token, &treasury), 300);
+    assert_eq!(
+        e.auths(),
+        std::vec![(
+            admin.clone(),
+            AuthorizedInvocation {
+                function: AuthorizedFunction::Contract((
+                    documents.address.clone(),
+                    Symbol::new(&e, "withdraw_fees"),
+                    (&fee_token, &treasury).into_val(&e),
+                )),
+                sub_invocations: std::vec![]
+            }
+        )]
+    );
+    assert_eq!(fee_token_client.balance(&treasury), 300);
+    assert_eq!(fee_token_client.balance(&documents.address), 0);
+}
+
+#[test]
+fn test_add_extra_signers() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let admin = Address::random(&e);
+    let owner = Address::random(&e);
+    let signer1 = Address::random(&e);
+    let signer2 = Address::random(&e);
+    let signer3 = Address::random(&e);
+    let documents = create_documents(&e, &admin);
+
+    let hash = BytesN::from_array(&e, &[1; 32]);
+    let uri = String::from_slice(&e, "uri1");
+    documents.safe_mint(
+        &owner,
+        &owner,
+        &None,
+        &uri,
+        &vec![&e, signer1.clone(), signer2.clone()],
+        &hash,
+        &1000,
+        &None,
+    );
+    documents.sign_document(&hash, &signer1, &SignatureStatus::Signed, &1, &None, &None);
+
+    let signers = documents.add_extra_signers(&vec![&e, signer3.clone()], &1);
+    assert_eq!(
+        e.auths(),
+        std::vec![(
+            owner.clone(),
+            AuthorizedInvocation {
+                function: AuthorizedFunction::Contract((
+                    documents.address.clone(),
+                    Symbol::new(&e, "add_extra_signers"),
+                    (vec![&e, signer3.clone()], 1_u32).into_val(&e),
+                )),
+                sub_invocations: std::vec![]
+            }
+        )]
+    );
+    assert_eq!(
+        signers,
+        vec![&e, signer1.clone(), signer2.clone(), signer3.clone()]
+    );
+
+    let document = signature_statuses(&documents, &1);
+    assert_eq!(document.get(signer1.clone()), Some(SignatureStatus::Signed));
+    assert_eq!(
+        document.get(signer2.clone()),
+        Some(SignatureStatus::Waiting)
+    );
+    assert_eq!(
+        document.get(signer3.clone()),
+        Some(SignatureStatus::Waiting)
+    );
+
+    documents.sign_document(&hash, &signer3, &SignatureStatus::Signed, &1, &None, &None);
+    assert_eq!(
+        signature_statuses(&documents, &1).get(signer3.clone()),
+        Some(SignatureStatus::Signed)
+    );
+}
+
+#[test]
+fn test_remove_and_replace_signer() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let admin = Address::random(&e);
+    let owner = Address::random(&e);
+    let signer1 = Address::random(&e);
+    let signer2 = Address::random(&e);
+    let signer3 = Address::random(&e);
+    let signer4 = Address::random(&e);
+    let documents = create_documents(&e, &admin);
+
+    let hash = BytesN::from_array(&e, &[1; 32]);
+    let uri = String::from_slice(&e, "uri1");
+    documents.safe_mint(
+        &owner,
+        &owner,
+        &None,
+        &uri,
+        &vec![&e, signer1.clone(), signer2.clone(), signer3.clone()],
+        &hash,
+        &1000,
+        &None,
+    );
+    documents.sign_document(&hash, &signer1, &SignatureStatus::Signed, &1, &None, &None);
+
+    let signers = documents.remove_signer(&1, &signer2);
+    assert_eq!(
+        e.auths(),
+        std::vec![(
+            owner.clone(),
+            AuthorizedInvocation {
+                function: AuthorizedFunction::Contract((
+                    documents.address.clone(),
+                    Symbol::new(&e, "remove_signer"),
+                    (1_u32, &signer2).into_val(&e),
+                )),
+                sub_invocations: std::vec![]
+            }
+        )]
+    );
+    assert_eq!(signers, vec![&e, signer1.clone(), signer3.clone()]);
+    assert_eq!(
+        signature_statuses(&documents, &1).get(signer2.clone()),
+        None
+    );
+
+    e.ledger().with_mut(|li| li.timestamp = 42);
+    let signers = documents.replace_signer(&1, &signer3, &signer4);
+    assert_eq!(signers, vec![&e, signer1.clone(), signer4.clone()]);
+
+    let document = signature_statuses(&documents, &1);
+    assert_eq!(document.get(signer3.clone()), None);
+    assert_eq!(
+        document.get(signer4.clone()),
+        Some(SignatureStatus::Waiting)
+    );
+    assert_eq!(
+        documents.get_signer_replacements(&1),
+        vec![
+            &e,
+            SignerReplacement {
+                previous: signer3.clone(),
+                replacement: signer4.clone(),
+                timestamp: 42,
+            }
+        ]
+    );
+}
+
+#[test]
+fn test_document_state() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let admin = Address::random(&e);
+    let owner = Address::random(&e);
+    let signer1 = Address::random(&e);
+    let signer2 = Address::random(&e);
+    let documents = create_documents(&e, &admin);
+
+    let hash = BytesN::from_array(&e, &[1; 32]);
+    let uri = String::from_slice(&e, "uri1");
+    let signers = vec![&e, signer1.clone(), signer2.clone()];
+    documents.safe_mint(&owner, &owner, &None, &uri, &signers, &hash, &1000, &None);
+    documents.safe_mint(&owner, &owner, &None, &uri, &signers, &hash, &1000, &None);
+    documents.safe_mint(&owner, &owner, &None, &uri, &signers, &hash, &1000, &None);
+    assert_eq!(documents.get_document_state(&1), DocumentState::Open);
+
+    documents.sign_document(&hash, &signer1, &SignatureStatus::Signed, &1, &None, &None);
+    assert_eq!(documents.get_document_state(&1), DocumentState::Open);
+    documents.sign_document(&hash, &signer2, &SignatureStatus::Signed, &1, &None, &None);
+    assert_eq!(documents.get_document_state(&1), DocumentState::Completed);
+
+    documents.sign_document(
+        &hash,
+        &signer1,
+        &SignatureStatus::Rejected,
+        &2,
+        &None,
+        &None,
+    );
+    assert_eq!(documents.get_document_state(&2), DocumentState::Rejected);
+
+    documents.sign_document(&hash, &signer1, &SignatureStatus::Signed, &3, &None, &None);
+    e.ledger().with_mut(|li| li.timestamp = 1001);
+    assert_eq!(documents.get_document_state(&3), DocumentState::Expired);
+    assert_eq!(documents.get_document_state(&1), DocumentState::Completed);
+}
+
+#[test]
+fn test_cancel_and_void_document() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let admin = Address::random(&e);
+    let owner = Address::random(&e);
+    let signer1 = Address::random(&e);
+    let signer2 = Address::random(&e);
+    let documents = create_documents(&e, &admin);
+
+    let hash = BytesN::from_array(&e, &[1; 32]);
+    let uri = String::from_slice(&e, "uri1");
+    let reason = BytesN::from_array(&e, &[1; 32]);
+    let signers = vec![&e, signer1.clone(), signer2.clone()];
+    for token_id in 1..4u32 {
+        assert_eq!(
+            documents.safe_mint(&owner, &owner, &None, &uri, &signers, &hash, &1000, &None),
+            token_id
+        );
+    }
+
+    e.ledger().with_mut(|li| li.timestamp = 10);
+    documents.sign_document(&hash, &signer1, &SignatureStatus::Signed, &1, &None, &None);
+    assert_eq!(
+        documents.cancel_document(&1, &reason),
+        DocumentState::Cancelled
+    );
+    assert_eq!(documents.get_document_state(&1), DocumentState::Cancelled);
+    assert_eq!(
+        documents.get_document_closure(&1),
+        Some(DocumentClosure {
+            state: DocumentState::Cancelled,
+            closed_by: owner.clone(),
+            reason_hash: reason.clone(),
+            timestamp: 10,
+        })
+    );
+
+    for token_id in 2..4u32 {
+        documents.sign_document(
+            &hash,
+            &signer1,
+            &SignatureStatus::Signed,
+            &token_id,
+            &None,
+            &None,
+        );
+        documents.sign_document(
+            &hash,
+            &signer2,
+            &SignatureStatus::Signed,
+            &token_id,
+            &None,
+            &None,
+        );
+    }
+
+    documents.void_document(&admin, &2, &reason);
+    assert_eq!(documents.get_document_state(&2), DocumentState::Voided);
+
+    documents.void_document(&owner, &3, &reason);
+    let authorizers: std::vec::Vec<Address> = e.auths().into_iter().map(|(a, _)| a).collect();
+    assert_eq!(
+        authorizers,
+        std::vec![owner.clone(), signer1.clone(), signer2.clone()]
+    );
+    assert_eq!(documents.get_document_state(&3), DocumentState::Voided);
+    assert_eq!(documents.get_document_closure(&3).unwrap().closed_by, owner);
+}
+
+#[test]
+fn test_sequential_signing() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let admin = Address::random(&e);
+    let owner = Address::random(&e);
+    let employee = Address::random(&e);
+    let manager = Address::random(&e);
+    let legal = Address::random(&e);
+    let documents = create_documents(&e, &admin);
+
+    let hash = BytesN::from_array(&e, &[1; 32]);
+    let uri = String::from_slice(&e, "uri1");
+    documents.safe_mint(
+        &owner,
+        &owner,
+        &None,
+        &uri,
+        &vec![&e, employee.clone(), manager.clone(), legal.clone()],
+        &hash,
+        &1000,
+        &Some(DocumentOptions {
+            signing_order: SigningOrder::Sequential,
+            threshold: 0,
+            roles: Map::new(&e),
+            rejection_policy: RejectionPolicy::Terminate,
+            hash_algorithm: HashAlgorithm::Sha256,
+            file_count: 1,
+        }),
+    );
+    documents.safe_mint(
+        &owner,
+        &owner,
+        &None,
+        &uri,
+        &vec![&e, employee.clone(), manager.clone()],
+        &hash,
+        &1000,
+        &None,
+    );
+
+    assert_eq!(documents.next_signer(&1), Some(employee.clone()));
+    documents.sign_document(&hash, &employee, &SignatureStatus::Signed, &1, &None, &None);
+    assert_eq!(documents.next_signer(&1), Some(manager.clone()));
+    documents.sign_document(&hash, &manager, &SignatureStatus::Signed, &1, &None, &None);
+    assert_eq!(documents.next_signer(&1), Some(legal.clone()));
+    documents.sign_document(&hash, &legal, &SignatureStatus::Signed, &1, &None, &None);
+    assert_eq!(documents.next_signer(&1), None);
+    assert_eq!(documents.get_document_state(&1), DocumentState::Completed);
+
+    assert_eq!(documents.next_signer(&2), None);
+    documents.sign_document(&hash, &manager, &SignatureStatus::Signed, &2, &None, &None);
+    assert_eq!(
+        signature_statuses(&documents, &2).get(manager.clone()),
+        Some(SignatureStatus::Signed)
+    );
+}
+
+#[test]
+fn test_quorum_completion() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let admin = Address::random(&e);
+    let owner = Address::random(&e);
+    let member1 = Address::random(&e);
+    let member2 = Address::random(&e);
+    let member3 = Address::random(&e);
+    let documents = create_documents(&e, &admin);
+
+    let hash = BytesN::from_array(&e, &[1; 32]);
+    let uri = String::from_slice(&e, "uri1");
+    documents.safe_mint(
+        &owner,
+        &owner,
+        &None,
+        &uri,
+        &vec![&e, member1.clone(), member2.clone(), member3.clone()],
+        &hash,
+        &1000,
+        &Some(DocumentOptions {
+            signing_order: SigningOrder::Parallel,
+            threshold: 2,
+            roles: Map::new(&e),
+            rejection_policy: RejectionPolicy::Terminate,
+            hash_algorithm: HashAlgorithm::Sha256,
+            file_count: 1,
+        }),
+    );
+    assert_eq!(
+        documents.get_signing_progress(&1),
+        SigningProgress {
+            threshold: 2,
+            signed: 0,
+            signers: 3
+        }
+    );
+
+    documents.sign_document(&hash, &member1, &SignatureStatus::Signed, &1, &None, &None);
+    assert_eq!(documents.get_document_state(&1), DocumentState::Open);
+    documents.sign_document(&hash, &member3, &SignatureStatus::Signed, &1, &None, &None);
+    assert_eq!(documents.get_document_state(&1), DocumentState::Completed);
+    assert_eq!(
+        documents.get_signing_progress(&1),
+        SigningProgress {
+            threshold: 2,
+            signed: 2,
+            signers: 3
+        }
+    );
+}
+
+#[test]
+fn test_signer_roles() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let admin = Address::random(&e);
+    let owner = Address::random(&e);
+    let signer = Address::random(&e);
+    let approver = Address::random(&e);
+    let witness = Address::random(&e);
+    let viewer = Address::random(&e);
+    let documents = create_documents(&e, &admin);
+
+    let hash = BytesN::from_array(&e, &[1; 32]);
+    let uri = String::from_slice(&e, "uri1");
+    documents.safe_mint(
+        &owner,
+        &owner,
+        &None,
+        &uri,
+        &vec![
+            &e,
+            signer.clone(),
+            approver.clone(),
+            witness.clone(),
+            viewer.clone(),
+        ],
+        &hash,
+        &1000,
+        &Some(DocumentOptions {
+            signing_order: SigningOrder::Parallel,
+            threshold: 0,
+            roles: map![
+                &e,
+                (approver.clone(), SignerRole::Approver),
+                (witness.clone(), SignerRole::Witness(signer.clone())),
+                (viewer.clone(), SignerRole::Viewer)
+            ],
+            rejection_policy: RejectionPolicy::Terminate,
+            hash_algorithm: HashAlgorithm::Sha256,
+            file_count: 1,
+        }),
+    );
+
+    let document = documents.get_document(&1);
+    assert_eq!(
+        document.get(signer.clone()),
+        Some(SignerEntry {
+            status: SignatureStatus::Waiting,
+            role: SignerRole::Signer,
+            acted_by: ActedBy::Signer
+        })
+    );
+    assert_eq!(
+        document.get(witness.clone()),
+        Some(SignerEntry {
+            status: SignatureStatus::Waiting,
+            role: SignerRole::Witness(signer.clone()),
+            acted_by: ActedBy::Signer
+        })
+    );
+    assert_eq!(
+        document.get(viewer.clone()),
+        Some(SignerEntry {
+            status: SignatureStatus::NotASigner,
+            role: SignerRole::Viewer,
+            acted_by: ActedBy::Signer
+        })
+    );
+    assert_eq!(documents.get_signing_progress(&1).signers, 3);
+
+    documents.sign_document(&hash, &signer, &SignatureStatus::Signed, &1, &None, &None);
+    documents.sign_document(&hash, &witness, &SignatureStatus::Signed, &1, &None, &None);
+    assert_eq!(documents.get_document_state(&1), DocumentState::Open);
+    documents.sign_document(&hash, &approver, &SignatureStatus::Signed, &1, &None, &None);
+    assert_eq!(documents.get_document_state(&1), DocumentState::Completed);
+}
+
+#[test]
+fn test_delegated_signing() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let admin = Address::random(&e);
+    let owner = Address::random(&e);
+    let executive = Address::random(&e);
+    let assistant = Address::random(&e);
+    let documents = create_documents(&e, &admin);
+
+    let hash = BytesN::from_array(&e, &[1; 32]);
+    let uri = String::from_slice(&e, "uri1");
+    documents.safe_mint(
+        &owner,
+        &owner,
+        &None,
+        &uri,
+        &vec![&e, executive.clone()],
+        &hash,
+        &1000,
+        &None,
+    );
+
+    documents.add_delegation(&executive, &assistant, &DelegationScope::Document(1), &500);
+    assert_eq!(
+        e.auths(),
+        std::vec![(
+            executive.clone(),
+            AuthorizedInvocation {
+                function: AuthorizedFunction::Contract((
+                    documents.address.clone(),
+                    Symbol::new(&e, "add_delegation"),
+                    (
+                        executive.clone(),
+                        assistant.clone(),
+                        DelegationScope::Document(1),
+                        500u64
+                    )
+                        .into_val(&e),
+                )),
+                sub_invocations: std::vec![]
+            }
+        )]
+    );
+    assert_eq!(
+        documents.get_delegations(&executive),
+        vec![
+            &e,
+            Delegation {
+                delegate: assistant.clone(),
+                scope: DelegationScope::Document(1),
+                expires_at: 500,
+            }
+        ]
+    );
+
+    documents.sign_document(
+        &hash,
+        &executive,
+        &SignatureStatus::Signed,
+        &1,
+        &Some(assistant.clone()),
+        &None,
+    );
+    assert_eq!(
+        documents.get_document(&1).get(executive.clone()),
+        Some(SignerEntry {
+            status: SignatureStatus::Signed,
+            role: SignerRole::Signer,
+            acted_by: ActedBy::Delegate(assistant.clone()),
+        })
+    );
+    assert_eq!(documents.get_document_state(&1), DocumentState::Completed);
+
+    documents.revoke_delegation(&executive, &assistant);
+    assert_eq!(documents.get_delegations(&executive), vec![&e]);
+}
+
+#[test]
+fn test_revoke_signature() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let admin = Address::random(&e);
+    let owner = Address::random(&e);
+    let signer1 = Address::random(&e);
+    let signer2 = Address::random(&e);
+    let documents = create_documents(&e, &admin);
+
+    let hash = BytesN::from_array(&e, &[1; 32]);
+    let uri = String::from_slice(&e, "uri1");
+    documents.safe_mint(
+        &owner,
+        &owner,
+        &None,
+        &uri,
+        &vec![&e, signer1.clone(), signer2.clone()],
+        &hash,
+        &1000,
+        &None,
+    );
+
+    e.ledger().with_mut(|li| li.timestamp = 10);
+    documents.sign_document(&hash, &signer1, &SignatureStatus::Signed, &1, &None, &None);
+    assert_eq!(documents.get_nonces(&signer1), 1);
+    let receipt = documents.get_signature_receipt(&1, &signer1).unwrap();
+
+    e.ledger().with_mut(|li| li.timestamp = 20);
+    let signings = documents.revoke_signature(&1, &signer1);
+    assert_eq!(
+        e.auths(),
+        std::vec![(
+            signer1.clone(),
+            AuthorizedInvocation {
+                function: AuthorizedFunction::Contract((
+                    documents.address.clone(),
+                    Symbol::new(&e, "revoke_signature"),
+                    (1u32, signer1.clone()).into_val(&e),
+                )),
+                sub_invocations: std::vec![]
+            }
+        )]
+    );
+    assert_eq!(
+        signings,
+        map![
+            &e,
+            (signer1.clone(), SignatureStatus::Waiting),
+            (signer2.clone(), SignatureStatus::Waiting)
+        ]
+    );
+    assert_eq!(documents.get_nonces(&signer1), 2);
+    assert_eq!(documents.get_document_state(&1), DocumentState::Open);
+    assert_eq!(documents.get_signature_receipt(&1, &signer1), None);
+    assert_eq!(
+        documents.get_revocations(&1, &signer1),
+        vec![
+            &e,
+            SignatureRevocation {
+                revoked: RevokedReceipt::Receipt(receipt),
+                nonce: 1,
+                timestamp: 20,
+                sequence: e.ledger().sequence(),
+            }
+        ]
+    );
+
+    documents.sign_document(&hash, &signer2, &SignatureStatus::Signed, &1, &None, &None);
+    assert_eq!(documents.get_document_state(&1), DocumentState::Open);
+    documents.sign_document(&hash, &signer1, &SignatureStatus::Signed, &1, &None, &None);
+    assert_eq!(documents.get_document_state(&1), DocumentState::Completed);
+}
+
+#[test]
+fn test_rejection_policy() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let admin = Address::random(&e);
+    let owner = Address::random(&e);
+    let member1 = Address::random(&e);
+    let member2 = Address::random(&e);
+    let member3 = Address::random(&e);
+    let documents = create_documents(&e, &admin);
+
+    let hash = BytesN::from_array(&e, &[1; 32]);
+    let uri = String::from_slice(&e, "uri1");
+    let reason = BytesN::from_array(&e, &[7; 32]);
+    let signers = vec![&e, member1.clone(), member2.clone(), member3.clone()];
+    documents.safe_mint(
+        &owner,
+        &owner,
+        &None,
+        &uri,
+        &signers,
+        &hash,
+        &1000,
+        &Some(DocumentOptions {
+            signing_order: SigningOrder::Parallel,
+            threshold: 2,
+            roles: Map::new(&e),
+            rejection_policy: RejectionPolicy::Continue,
+            hash_algorithm: HashAlgorithm::Sha256,
+            file_count: 1,
+        }),
+    );
+
+    e.ledger().with_mut(|li| li.timestamp = 10);
+    documents.sign_document(
+        &hash,
+        &member1,
+        &SignatureStatus::Rejected,
+        &1,
+        &None,
+        &Some(reason.clone()),
+    );
+    assert_eq!(documents.get_document_state(&1), DocumentState::Open);
+    assert_eq!(
+        documents.get_rejections(&1),
+        map![
+            &e,
+            (
+                member1.clone(),
+                Rejection {
+                    reason_hash: reason.clone(),
+                    timestamp: 10,
+                }
+            )
+        ]
+    );
+
+    documents.sign_document(&hash, &member2, &SignatureStatus::Signed, &1, &None, &None);
+    documents.sign_document(&hash, &member3, &SignatureStatus::Signed, &1, &None, &None);
+    assert_eq!(documents.get_document_state(&1), DocumentState::Completed);
+
+    documents.safe_mint(&owner, &owner, &None, &uri, &signers, &hash, &1000, &None);
+    documents.sign_document(
+        &hash,
+        &member1,
+        &SignatureStatus::Rejected,
+        &2,
+        &None,
+        &Some(reason.clone()),
+    );
+    assert_eq!(documents.get_document_state(&2), DocumentState::Rejected);
+
+    documents.safe_mint(
+        &owner,
+        &owner,
+        &None,
+        &uri,
+        &signers,
+        &hash,
+        &1000,
+        &Some(DocumentOptions {
+            signing_order: SigningOrder::Parallel,
+            threshold: 0,
+            roles: Map::new(&e),
+            rejection_policy: RejectionPolicy::Continue,
+            hash_algorithm: HashAlgorithm::Sha256,
+            file_count: 1,
+        }),
+    );
+    documents.sign_document(
+        &hash,
+        &member2,
+        &SignatureStatus::Rejected,
+        &3,
+        &None,
+        &None,
+    );
+    assert_eq!(documents.get_document_state(&3), DocumentState::Rejected);
+    assert_eq!(documents.get_rejections(&3), Map::new(&e));
+}
+
+#[test]
+fn test_signature_receipts() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let admin = Address::random(&e);
+    let owner = Address::random(&e);
+    let signer1 = Address::random(&e);
+    let signer2 = Address::random(&e);
+    let assistant = Address::random(&e);
+    let documents = create_documents(&e, &admin);
+
+    let hash = BytesN::from_array(&e, &[1; 32]);
+    let uri = String::from_slice(&e, "uri1");
+    documents.safe_mint(
+        &owner,
+        &owner,
+        &None,
+        &uri,
+        &vec![&e, signer1.clone(), signer2.clone()],
+        &hash,
+        &1000,
+        &None,
+    );
+    assert_eq!(documents.get_signature_receipt(&1, &signer1), None);
+
+    e.ledger().with_mut(|li| {
+        li.timestamp = 20;
+        li.sequence_number = 7;
+    });
+    documents.sign_document(&hash, &signer1, &SignatureStatus::Signed, &1, &None, &None);
+    assert_eq!(
+        documents.get_signature_receipt(&1, &signer1),
+        Some(SignatureReceipt {
+            status: SignatureStatus::Signed,
+            document_hash: hash.clone(),
+            nonce: 0,
+            timestamp: 20,
+            sequence: 7,
+            submitter: signer1.clone(),
+        })
+    );
+
+    documents.revoke_signature(&1, &signer1);
+    assert_eq!(documents.get_signature_receipt(&1, &signer1), None);
+
+    e.ledger().with_mut(|li| {
+        li.timestamp = 30;
+        li.sequence_number = 9;
+    });
+    documents.add_delegation(&signer2, &assistant, &DelegationScope::AllDocuments, &500);
+    documents.sign_document(
+        &hash,
+        &signer2,
+        &SignatureStatus::Signed,
+        &1,
+        &Some(assistant.clone()),
+        &None,
+    );
+    assert_eq!(
+        documents.get_signature_receipt(&1, &signer2),
+        Some(SignatureReceipt {
+            status: SignatureStatus::Signed,
+            document_hash: hash.clone(),
+            nonce: 0,
+            timestamp: 30,
+            sequence: 9,
+            submitter: assistant.clone(),
+        })
+    );
+}
+
+#[test]
+fn test_document_events() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let admin = Address::random(&e);
+    let owner = Address::random(&e);
+    let signer1 = Address::random(&e);
+    let signer2 = Address::random(&e);
+    let documents = create_documents(&e, &admin);
+
+    let hash = BytesN::from_array(&e, &[1; 32]);
+    let uri = String::from_slice(&e, "uri1");
+    documents.safe_mint(
+        &owner,
+        &owner,
+        &None,
+        &uri,
+        &vec![&e, signer1.clone(), signer2.clone()],
+        &hash,
+        &1000,
+        &None,
+    );
+    assert_eq!(
+        e.events().all(),
+        vec![
+            &e,
+            (
+                documents.address.clone(),
+                (Symbol::new(&e, "document_created"), 1u32).into_val(&e),
+                DocumentCreated {
+                    owner: owner.clone(),
+                    uri: uri.clone(),
+                    document_hash: hash.clone(),
+                    deadline: 1000,
+                    signing_order: SigningOrder::Parallel,
+                    threshold: 0,
+                    rejection_policy: RejectionPolicy::Terminate,
+                    hash_algorithm: HashAlgorithm::Sha256,
+                    file_count: 1,
+                }
+                .into_val(&e),
+            ),
+            (
+                documents.address.clone(),
+                (Symbol::new(&e, "signer_added"), 1u32).into_val(&e),
+                SignerAdded {
+                    signer: signer1.clone(),
+                    role: SignerRole::Signer,
+                }
+                .into_val(&e),
+            ),
+            (
+                documents.address.clone(),
+                (Symbol::new(&e, "signer_added"), 1u32).into_val(&e),
+                SignerAdded {
+                    signer: signer2.clone(),
+                    role: SignerRole::Signer,
+                }
+                .into_val(&e),
+            ),
+        ]
+    );
+
+    documents.sign_document(&hash, &signer1, &SignatureStatus::Signed, &1, &None, &None);
+    documents.sign_document(&hash, &signer2, &SignatureStatus::Signed, &1, &None, &None);
+    let events = e.events().all();
+    assert_eq!(
+        events.slice(events.len() - 2..),
+        vec![
+            &e,
+            (
+                documents.address.clone(),
+                (symbol_short!("signed"), 1u32).into_val(&e),
+                Signed {
+                    signer: signer2.clone(),
+                    acted_by: ActedBy::Signer,
+                    submitter: signer2.clone(),
+                    nonce: 0,
+                }
+                .into_val(&e),
+            ),
+            (
+                documents.address.clone(),
+                (symbol_short!("completed"), 1u32).into_val(&e),
+                Completed {
+                    signed: 2,
+                    threshold: 2,
+                }
+                .into_val(&e),
+            ),
+        ]
+    );
+
+    documents.safe_mint(
+        &owner,
+        &owner,
+        &None,
+        &uri,
+        &vec![&e, signer1.clone()],
+        &hash,
+        &1000,
+        &None,
+    );
+    let reason = BytesN::from_array(&e, &[3; 32]);
+    documents.sign_document(
+        &hash,
+        &signer1,
+        &SignatureStatus::Rejected,
+        &2,
+        &None,
+        &Some(reason.clone()),
+    );
+    let events = e.events().all();
+    assert_eq!(
+        events.slice(events.len() - 2..),
+        vec![
+            &e,
+            (
+                documents.address.clone(),
+                (symbol_short!("rejected"), 2u32).into_val(&e),
+                Rejected {
+                    signer: signer1.clone(),
+                    acted_by: ActedBy::Signer,
+                    submitter: signer1.clone(),
+                    nonce: 1,
+                    reason: RejectionReason::Hash(reason),
+                }
+                .into_val(&e),
+            ),
+            (
+                documents.address.clone(),
+                (Symbol::new(&e, "document_rejected"), 2u32).into_val(&e),
+                DocumentRejected { rejected: 1 }.into_val(&e),
+            ),
+        ]
+    );
+
+    documents.safe_mint(
+        &owner,
+        &owner,
+        &None,
+        &uri,
+        &vec![&e, signer2.clone()],
+        &hash,
+        &1000,
+        &None,
+    );
+    e.ledger().with_mut(|li| li.timestamp = 1001);
+    assert_eq!(documents.expire_document(&3), DocumentState::Expired);
+    let events = e.events().all();
+    assert_eq!(
+        events.slice(events.len() - 1..),
+        vec![
+            &e,
+            (
+                documents.address.clone(),
+                (symbol_short!("expired"), 3u32).into_val(&e),
+                Expired { deadline: 1000 }.into_val(&e),
+            ),
+        ]
+    );
+    assert_eq!(documents.get_document_state(&3), DocumentState::Expired);
+}
+
+#[test]
+fn test_amend_document() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let admin = Address::random(&e);
+    let owner = Address::random(&e);
+    let signer1 = Address::random(&e);
+    let signer2 = Address::random(&e);
+    let documents = create_documents(&e, &admin);
+
+    let hash = BytesN::from_array(&e, &[1; 32]);
+    let uri = String::from_slice(&e, "uri1");
+    documents.safe_mint(
+        &owner,
+        &owner,
+        &None,
+        &uri,
+        &vec![&e, signer1.clone(), signer2.clone()],
+        &hash,
+        &1000,
+        &None,
+    );
+    documents.sign_document(&hash, &signer1, &SignatureStatus::Signed, &1, &None, &None);
+    documents.sign_document(&hash, &signer2, &SignatureStatus::Signed, &1, &None, &None);
+    assert_eq!(documents.get_document_state(&1), DocumentState::Completed);
+
+    // The deadline itself is still in time for an amendment.
+    e.ledger().with_mut(|li| li.timestamp = 1000);
+    let new_hash = BytesN::from_array(&e, &[2; 32]);
+    let new_uri = String::from_slice(&e, "uri2");
+    assert_eq!(
+        documents.amend_document(&1, &new_hash, &new_uri, &HashAlgorithm::Sha256Merkle, &2),
+        2
+    );
+    assert_eq!(
+        e.auths(),
+        std::vec![(
+            owner.clone(),
+            AuthorizedInvocation {
+                function: AuthorizedFunction::Contract((
+                    documents.address.clone(),
+                    Symbol::new(&e, "amend_document"),
+                    (
+                        1u32,
+                        new_hash.clone(),
+                        new_uri.clone(),
+                        HashAlgorithm::Sha256Merkle,
+                        2u32,
+                    )
+                        .into_val(&e),
+                )),
+                sub_invocations: std::vec![]
+            }
+        )]
+    );
+    assert_eq!(documents.get_document_state(&1), DocumentState::Open);
+    assert_eq!(
+        signature_statuses(&documents, &1),
+        map![
+            &e,
+            (signer1.clone(), SignatureStatus::Waiting),
+            (signer2.clone(), SignatureStatus::Waiting)
+        ]
+    );
+    assert_eq!(documents.get_signature_receipt(&1, &signer1), None);
+    assert_eq!(documents.get_token_uri(&1), new_uri);
+
+    documents.sign_document(
+        &new_hash,
+        &signer1,
+        &SignatureStatus::Signed,
+        &1,
+        &None,
+        &None,
+    );
+
+    let versions = documents.get_document_versions(&1);
+    assert_eq!(versions.len(), 2);
+
+    let first = versions.get_unchecked(0);
+    assert_eq!(first.version, 1);
+    assert_eq!(first.document_hash, hash);
+    assert_eq!(first.previous_hash, BytesN::from_array(&e, &[0; 32]));
+    assert_eq!(first.hash_algorithm, HashAlgorithm::Sha256);
+    assert_eq!(first.file_count, 1);
+    assert_eq!(first.state, DocumentState::Completed);
+    assert_eq!(
+        first
+            .signatures
+            .get(signer2.clone())
+            .map(|entry| entry.status),
+        Some(SignatureStatus::Signed)
+    );
+    assert_eq!(first.receipts.len(), 2);
+
+    let second = versions.get_unchecked(1);
+    assert_eq!(second.version, 2);
+    assert_eq!(second.document_hash, new_hash);
+    assert_eq!(second.previous_hash, hash);
+    assert_eq!(second.uri, new_uri);
+    assert_eq!(second.hash_algorithm, HashAlgorithm::Sha256Merkle);
+    assert_eq!(second.file_count, 2);
+    assert_eq!(second.state, DocumentState::Open);
+    assert_eq!(
+        second
+            .signatures
+            .get(signer1.clone())
+            .map(|entry| entry.status),
+        Some(SignatureStatus::Signed)
+    );
+    assert_eq!(second.receipts.len(), 1);
+}
+
+fn hash_leaf(e: &Env, leaf: &BytesN<32>) -> BytesN<32> {
+    let mut data = Bytes::from_array(e, &[0]);
+    data.append(&Bytes::from_array(e, &leaf.to_array()));
+    e.crypto().sha256(&data)
+}
+
+fn hash_pair(e: &Env, a: &BytesN<32>, b: &BytesN<32>) -> BytesN<32> {
+    let (left, right) = if a <= b { (a, b) } else { (b, a) };
+    let mut data = Bytes::from_array(e, &[1]);
+    data.append(&Bytes::from_array(e, &left.to_array()));
+    data.append(&Bytes::from_array(e, &right.to_array()));
+    e.crypto().sha256(&data)
+}
+
+#[test]
+fn test_verify_file_in_document() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let admin = Address::random(&e);
+    let owner = Address::random(&e);
+    let signer = Address::random(&e);
+    let documents = create_documents(&e, &admin);
+
+    let contract = e.crypto().sha256(&Bytes::from_slice(&e, b"contract"));
+    let annex_a = e.crypto().sha256(&Bytes::from_slice(&e, b"annex a"));
+    let annex_b = e.crypto().sha256(&Bytes::from_slice(&e, b"annex b"));
+    let pair = hash_pair(&e, &hash_leaf(&e, &contract), &hash_leaf(&e, &annex_a));
+    let root = hash_pair(&e, &pair, &hash_leaf(&e, &annex_b));
+
+    documents.safe_mint(
+        &owner,
+        &owner,
+        &None,
+        &String::from_slice(&e, "uri1"),
+        &vec![&e, signer.clone()],
+        &root,
+        &1000,
+        &Some(DocumentOptions {
+            signing_order: SigningOrder::Parallel,
+            threshold: 0,
+            roles: Map::new(&e),
+            rejection_policy: RejectionPolicy::Terminate,
+            hash_algorithm: HashAlgorithm::Sha256Merkle,
+            file_count: 3,
+        }),
+    );
+
+    assert!(documents.verify_file_in_document(
+        &1,
+        &contract,
+        &vec![&e, hash_leaf(&e, &annex_a), hash_leaf(&e, &annex_b)]
+    ));
+    assert!(documents.verify_file_in_document(
+        &1,
+        &annex_a,
+        &vec![&e, hash_leaf(&e, &contract), hash_leaf(&e, &annex_b)]
+    ));
+    assert!(documents.verify_file_in_document(&1, &annex_b, &vec![&e, pair.clone()]));
+
+    let forged = e.crypto().sha256(&Bytes::from_slice(&e, b"forged annex"));
+    assert!(!documents.verify_file_in_document(&1, &forged, &vec![&e, pair.clone()]));
+    assert!(!documents.verify_file_in_document(
+        &1,
+        &contract,
+        &vec![
+            &e,
+            hash_leaf(&e, &annex_a),
+            hash_leaf(&e, &annex_b),
+            pair.clone()
+        ]
+    ));
+
+    // Internal nodes are not files.
+    assert!(!documents.verify_file_in_document(&1, &pair, &vec![&e, hash_leaf(&e, &annex_b)]));
+    assert!(!documents.verify_file_in_document(&1, &root, &vec![&e]));
+
+    documents.safe_mint(
+        &owner,
+        &owner,
+        &None,
+        &String::from_slice(&e, "uri2"),
+        &vec![&e, signer.clone()],
+        &contract,
+        &1000,
+        &None,
+    );
+    assert!(documents.verify_file_in_document(&2, &contract, &vec![&e]));
+    assert!(!documents.verify_file_in_document(&2, &annex_a, &vec![&e]));
+}
+
+#[test]
+fn test_verify_document() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let admin = Address::random(&e);
+    let owner = Address::random(&e);
+    let signer = Address::random(&e);
+    let documents = create_documents(&e, &admin);
+
+    let content = Bytes::from_slice(&e, b"%PDF-1.7 employment contract");
+    documents.safe_mint(
+        &owner,
+        &owner,
+        &None,
+        &String::from_slice(&e, "uri1"),
+        &vec![&e, signer.clone()],
+        &e.crypto().sha256(&content),
+        &1000,
+        &None,
+    );
+
+    assert!(documents.verify_document(&1, &content));
+    assert!(!documents.verify_document(&1, &Bytes::from_slice(&e, b"%PDF-1.7 tampered")));
+    assert_eq!(
+        documents.get_td_hashes().get(1),
+        Some(e.crypto().sha256(&content))
+    );
+}
+
+#[test]
+fn test_find_by_hash() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let admin = Address::random(&e);
+    let owner = Address::random(&e);
+    let signer = Address::random(&e);
+    let documents = create_documents(&e, &admin);
+
+    let hash = BytesN::from_array(&e, &[1; 32]);
+    let uri = String::from_slice(&e, "uri1");
+    assert_eq!(documents.find_by_hash(&hash), vec![&e]);
+
+    documents.safe_mint(
+        &owner,
+        &owner,
+        &None,
+        &uri,
+        &vec![&e, signer.clone()],
+        &hash,
+        &1000,
+        &None,
+    );
+    documents.safe_mint(
+        &owner,
+        &owner,
+        &None,
+        &uri,
+        &vec![&e, signer.clone()],
+        &hash,
+        &1000,
+        &None,
+    );
+    documents.sign_document(&hash, &signer, &SignatureStatus::Signed, &1, &None, &None);
+    assert_eq!(
+        documents.find_by_hash(&hash),
+        vec![
+            &e,
+            DocumentMatch {
+                token_id: 1,
+                state: DocumentState::Completed,
+                current: true,
+                progress: SigningProgress {
+                    threshold: 1,
+                    signed: 1,
+                    signers: 1,
+                },
+            },
+            DocumentMatch {
+                token_id: 2,
+                state: DocumentState::Open,
+                current: true,
+                progress: SigningProgress {
+                    threshold: 1,
+                    signed: 0,
+                    signers: 1,
+                },
+            },
+        ]
+    );
+
+    documents.set_unique_hashes(&true);
+    assert_eq!(
+        e.auths(),
+        std::vec![(
+            admin.clone(),
+            AuthorizedInvocation {
+                function: AuthorizedFunction::Contract((
+                    documents.address.clone(),
+                    Symbol::new(&e, "set_unique_hashes"),
+                    (true,).into_val(&e),
+                )),
+                sub_invocations: std::vec![]
+            }
+        )]
+    );
+
+    assert!(documents.get_unique_hashes());
+
+    let new_hash = BytesN::from_array(&e, &[2; 32]);
+    documents.amend_document(&2, &new_hash, &uri, &HashAlgorithm::Sha256, &1);
+    let matches = documents.find_by_hash(&hash);
+    assert_eq!(matches.len(), 2);
+    assert!(!matches.get_unchecked(1).current);
+    assert_eq!(
+        documents
+            .find_by_hash(&new_hash)
+            .iter()
+            .map(|found| found.token_id)
+            .collect::<std::vec::Vec<u32>>(),
+        std::vec![2]
+    );
+}
+
+/// Lowest expiration ledger among the persistent document entries, leaving
+/// out contract instances which are bumped with their own watermarks.
+fn min_persistent_expiration(e: &Env) -> u32 {
+    let host = e.host();
+    host.with_mut_storage(|storage| {
+        let budget = host.budget_cloned();
+        Ok(storage
+            .map
+            .iter(&budget)?
+            .filter_map(|(key, entry)| match (key.as_ref(), entry) {
+                (LedgerKey::ContractData(data), Some((_, Some(expiration))))
+                    if data.durability == ContractDataDurability::Persistent
+                        && data.key != ScVal::LedgerKeyContractInstance =>
+                {
+                    Some(*expiration)
+                }
+                _ => None,
+            })
+            .min()
+            .unwrap())
+    })
+    .unwrap()
+}
+
+fn persistent_expiration(e: &Env, key: &DataKey) -> u32 {
+    let key = ScVal::try_from_val(e, &IntoVal::<Env, Val>::into_val(key, e)).unwrap();
+    let host = e.host();
+    host.with_mut_storage(|storage| {
+        let budget = host.budget_cloned();
+        Ok(storage
+            .map
+            .iter(&budget)?
+            .find_map(|(ledger_key, entry)| match (ledger_key.as_ref(), entry) {
+                (LedgerKey::ContractData(data), Some((_, Some(expiration))))
+                    if data.durability == ContractDataDurability::Persistent && data.key == key =>
+                {
+                    Some(*expiration)
+                }
+                _ => None,
+            })
+            .unwrap())
+    })
+    .unwrap()
+}
+
+#[test]
+fn test_document_ttl() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let admin = Address::random(&e);
+    let owner = Address::random(&e);
+    let signer1 = Address::random(&e);
+    let signer2 = Address::random(&e);
+    let documents = create_documents(&e, &admin);
+
+    let hash = BytesN::from_array(&e, &[1; 32]);
+    documents.safe_mint(
+        &owner,
+        &owner,
+        &None,
+        &String::from_slice(&e, "uri1"),
+        &vec![&e, signer1.clone(), signer2.clone()],
+        &hash,
+        &u64::MAX,
+        &None,
+    );
+    assert!(
+        min_persistent_expiration(&e) >= e.ledger().sequence() + DOCUMENT_BUMP_AMOUNT_LOW_WATERMARK
+    );
+    documents.sign_document(&hash, &signer1, &SignatureStatus::Signed, &1, &None, &None);
+
+    e.ledger()
+        .with_mut(|li| li.sequence_number += INSTANCE_BUMP_AMOUNT_LOW_WATERMARK);
+    documents.extend_document_ttl(&1);
+    assert!(
+        min_persistent_expiration(&e) >= e.ledger().sequence() + DOCUMENT_BUMP_AMOUNT_LOW_WATERMARK
+    );
+
+    // Step past the initial TTL several times over with reads alone. They
+    // keep alive every entry the next signature loads.
+    let signature_key = |signer: &Address| SignatureDataKey {
+        token_id: 1,
+        signer: signer.clone(),
+    };
+    let loaded_keys = [
+        DataKey::Document(1),
+        DataKey::Signers(1),
+        DataKey::Signature(signature_key(&signer1)),
+        DataKey::Signature(signature_key(&signer2)),
+        DataKey::Receipt(signature_key(&signer1)),
+    ];
+    for step in 0..100 {
+        e.budget().reset_default();
+        e.ledger()
+            .with_mut(|li| li.sequence_number += INSTANCE_BUMP_AMOUNT_LOW_WATERMARK);
+        if step % 2 == 0 {
+            assert_eq!(documents.get_document_state(&1), DocumentState::Open);
+        } else {
+            assert_eq!(documents.get_document_view(&1).signers.len(), 2);
+        }
+        for key in loaded_keys.iter() {
+            assert!(persistent_expiration(&e, key) > e.ledger().sequence());
+        }
+    }
+    assert!(e.ledger().sequence() > 2 * DOCUMENT_BUMP_AMOUNT_HIGH_WATERMARK);
+
+    documents.sign_document(&hash, &signer2, &SignatureStatus::Signed, &1, &None, &None);
+    for key in loaded_keys.iter() {
+        assert!(
+            persistent_expiration(&e, key)
+                >= e.ledger().sequence() + DOCUMENT_BUMP_AMOUNT_LOW_WATERMARK
+        );
+    }
+    assert_eq!(documents.get_document_state(&1), DocumentState::Completed);
+}
+
+#[test]
+fn test_list_documents() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let admin = Address::random(&e);
+    let owner = Address::random(&e);
+    let signer1 = Address::random(&e);
+    let signer2 = Address::random(&e);
+    let documents = create_documents(&e, &admin);
+
+    let hash = BytesN::from_array(&e, &[1; 32]);
+    let uri = String::from_slice(&e, "uri1");
+    let signers = vec![&e, signer1.clone(), signer2.clone()];
+    for _ in 0..5 {
+        documents.safe_mint(&owner, &owner, &None, &uri, &signers, &hash, &1000, &None);
+    }
+
+    let page = documents.list_documents(&0, &2);
+    assert_eq!(page.next_cursor, 2);
+    assert_eq!(page.documents.len(), 2);
+    assert_eq!(page.documents.get_unchecked(0).token_id, 1);
+    assert_eq!(page.documents.get_unchecked(1).token_id, 2);
+
+    let page = documents.list_documents(&page.next_cursor, &2);
+    assert_eq!(page.next_cursor, 4);
+    assert_eq!(page.documents.get_unchecked(0).token_id, 3);
+    assert_eq!(page.documents.get_unchecked(1).token_id, 4);
+
+    // Documents minted between pages show up after the cursor.
+    documents.safe_mint(&owner, &owner, &None, &uri, &signers, &hash, &1000, &None);
+    let page = documents.list_documents(&page.next_cursor, &10);
+    assert_eq!(page.next_cursor, 6);
+    assert_eq!(page.documents.len(), 2);
+    assert_eq!(page.documents.get_unchecked(1).token_id, 6);
+
+    let page = documents.list_documents(&page.next_cursor, &10);
+    assert_eq!(page.next_cursor, 6);
+    assert!(page.documents.is_empty());
+
+    assert_eq!(documents.list_documents(&0, &100).documents.len(), 6);
+
+    // The unpaginated getters still cover a full page of documents.
+    for _ in 0..2 {
+        documents.safe_mint(&owner, &owner, &None, &uri, &signers, &hash, &1000, &None);
+    }
+    assert_eq!(documents.get_owners().len(), MAX_PAGE_SIZE);
+    assert_eq!(documents.get_documents().len(), MAX_PAGE_SIZE);
+
+    documents.safe_mint(&owner, &owner, &None, &uri, &signers, &hash, &1000, &None);
+    let page = documents.list_documents(&0, &100);
+    assert_eq!(page.documents.len(), MAX_PAGE_SIZE);
+    assert_eq!(page.next_cursor, MAX_PAGE_SIZE);
+    let page = documents.list_documents(&page.next_cursor, &100);
+    assert_eq!(page_token_ids(&e, &page), vec![&e, 9]);
+}
+
+#[test]
+fn test_max_page_cost() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let admin = Address::random(&e);
+    let owner = Address::random(&e);
+    let documents = create_documents(&e, &admin);
+
+    let mut signers: Vec<Address> = Vec::new(&e);
+    for _ in 0..5 {
+        signers.push_back(Address::random(&e));
+    }
+    let uri = String::from_slice(&e, "uri1");
+    for token_id in 1..=MAX_PAGE_SIZE {
+        let hash = BytesN::from_array(&e, &[token_id as u8; 32]);
+        e.budget().reset_default();
+        documents.safe_mint(&owner, &owner, &None, &uri, &signers, &hash, &1000, &None);
+        for signer in signers.iter() {
+            e.budget().reset_default();
+            documents.sign_document(
+                &hash,
+                &signer,
+                &SignatureStatus::Signed,
+                &token_id,
+                &None,
+                &None,
+            );
+        }
+    }
+
+    // A full page of fully signed documents fits in a single call's budget.
+    e.budget().reset_default();
+    let page = documents.list_documents(&0, &MAX_PAGE_SIZE);
+    assert_eq!(page.documents.len(), MAX_PAGE_SIZE);
+    assert!(page
+        .documents
+        .iter()
+        .all(|view| view.signers.len() == 5 && view.state == DocumentState::Completed));
+
+    e.budget().reset_default();
+    let page = documents.documents_for_signer(
+        &signers.get_unchecked(0),
+        &Some(SignatureStatus::Signed),
+        &0,
+        &MAX_PAGE_SIZE,
+    );
+    assert_eq!(page.documents.len(), MAX_PAGE_SIZE);
+
+    let mut token_ids: Vec<u32> = Vec::new(&e);
+    for token_id in (1..=MAX_PAGE_SIZE).rev() {
+        token_ids.push_back(token_id);
+    }
+    e.budget().reset_default();
+    let views = documents.get_document_views(&token_ids);
+    assert_eq!(views.len(), MAX_PAGE_SIZE);
+    assert_eq!(views.get_unchecked(0).token_id, MAX_PAGE_SIZE);
+}
+
+fn page_token_ids(e: &Env, page: &DocumentPage) -> Vec<u32> {
+    let mut token_ids = Vec::new(e);
+    for view in page.documents.iter() {
+        token_ids.push_back(view.token_id);
+    }
+    token_ids
+}
+
+#[test]
+fn test_documents_by_address() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let admin = Address::random(&e);
+    let owner1 = Address::random(&e);
+    let owner2 = Address::random(&e);
+    let signer1 = Address::random(&e);
+    let signer2 = Address::random(&e);
+    let signer3 = Address::random(&e);
+    let documents = create_documents(&e, &admin);
+
+    let hash = BytesN::from_array(&e, &[1; 32]);
+    let uri = String::from_slice(&e, "uri1");
+    let both = vec![&e, signer1.clone(), signer2.clone()];
+    documents.safe_mint(&owner1, &owner1, &None, &uri, &both, &hash, &1000, &None);
+    documents.safe_mint(
+        &owner1,
+        &owner1,
+        &None,
+        &uri,
+        &vec![&e, signer1.clone()],
+        &hash,
+        &1000,
+        &None,
+    );
+    documents.safe_mint(&owner2, &owner2, &None, &uri, &both, &hash, &1000, &None);
+
+    documents.sign_document(&hash, &signer1, &SignatureStatus::Signed, &1, &None, &None);
+    documents.add_extra_signers(&vec![&e, signer2.clone()], &2);
+    documents.replace_signer(&3, &signer2, &signer3);
+
+    let page = documents.documents_for_owner(&owner1, &0, &10);
+    assert_eq!(page_token_ids(&e, &page), vec![&e, 1, 2]);
+    assert_eq!(page.next_cursor, 2);
+    let page = documents.documents_for_owner(&owner2, &0, &10);
+    assert_eq!(page_token_ids(&e, &page), vec![&e, 3]);
+
+    let waiting = Some(SignatureStatus::Waiting);
+    let page = documents.documents_for_signer(&signer1, &waiting, &0, &10);
+    assert_eq!(page_token_ids(&e, &page), vec![&e, 2, 3]);
+    assert_eq!(page.next_cursor, 3);
+    let page = documents.documents_for_signer(&signer1, &Some(SignatureStatus::Signed), &0, &10);
+    assert_eq!(page_token_ids(&e, &page), vec![&e, 1]);
+    let page = documents.documents_for_signer(&signer1, &None, &1, &1);
+    assert_eq!(page_token_ids(&e, &page), vec![&e, 2]);
+    assert_eq!(page.next_cursor, 2);
+
+    let page = documents.documents_for_signer(&signer2, &None, &0, &10);
+    assert_eq!(page_token_ids(&e, &page), vec![&e, 1, 2]);
+    let page = documents.documents_for_signer(&signer3, &waiting, &0, &10);
+    assert_eq!(page_token_ids(&e, &page), vec![&e, 3]);
+
+    // A removed signer leaves a gap, so cursors handed out earlier still
+    // line up.
+    documents.remove_signer(&2, &signer2);
+    let page = documents.documents_for_signer(&signer2, &None, &0, &10);
+    assert_eq!(page_token_ids(&e, &page), vec![&e, 1]);
+    assert_eq!(page.next_cursor, 3);
+
+    // Documents that are no longer open are not awaiting anyone.
+    documents.cancel_document(&3, &BytesN::from_array(&e, &[9; 32]));
+    let page = documents.documents_for_signer(&signer3, &waiting, &0, &10);
+    assert!(page.documents.is_empty());
+    let page = documents.documents_for_signer(&signer3, &None, &0, &10);
+    assert_eq!(page_token_ids(&e, &page), vec![&e, 3]);
+    let page = documents.documents_for_signer(&signer1, &waiting, &0, &10);
+    assert_eq!(page_token_ids(&e, &page), vec![&e, 2]);
+}
+
+#[test]
+fn test_document_view() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let admin = Address::random(&e);
+    let owner = Address::random(&e);
+    let signer1 = Address::random(&e);
+    let signer2 = Address::random(&e);
+    let viewer = Address::random(&e);
+    let documents = create_documents(&e, &admin);
+
+    let hash = BytesN::from_array(&e, &[1; 32]);
+    let uri = String::from_slice(&e, "uri1");
+    let signers = vec![&e, signer1.clone(), signer2.clone(), viewer.clone()];
+    documents.safe_mint(
+        &owner,
+        &owner,
+        &None,
+        &uri,
+        &signers,
+        &hash,
+        &1000,
+        &Some(DocumentOptions {
+            signing_order: SigningOrder::Parallel,
+            threshold: 0,
+            roles: map![&e, (viewer.clone(), SignerRole::Viewer)],
+            rejection_policy: RejectionPolicy::Terminate,
+            hash_algorithm: HashAlgorithm::Sha256,
+            file_count: 1,
+        }),
+    );
+    documents.safe_mint(&owner, &owner, &None, &uri, &signers, &hash, &1000, &None);
+
+    e.ledger().with_mut(|li| li.timestamp = 10);
+    documents.sign_document(&hash, &signer1, &SignatureStatus::Signed, &1, &None, &None);
+
+    let view = documents.get_document_view(&1);
+    assert_eq!(
+        view,
+        DocumentView {
+            token_id: 1,
+            owner: owner.clone(),
+            uri: uri.clone(),
+            document_hash: hash.clone(),
+            deadline: 1000,
+            state: DocumentState::Open,
+            version: 1,
+            hash_algorithm: HashAlgorithm::Sha256,
+            rejection_policy: RejectionPolicy::Terminate,
+            signers: vec![
+                &e,
+                SignerView {
+                    signer: signer1.clone(),
+                    status: SignatureStatus::Signed,
+                    role: SignerRole::Signer,
+                    acted_by: ActedBy::Signer,
+                    timestamp: 10,
+                    rejection_reason: RejectionReason::Unspecified,
+                },
+                SignerView {
+                    signer: signer2.clone(),
+                    status: SignatureStatus::Waiting,
+                    role: SignerRole::Signer,
+                    acted_by: ActedBy::Signer,
+                    timestamp: 0,
+                    rejection_reason: RejectionReason::Unspecified,
+                },
+                SignerView {
+                    signer: viewer.clone(),
+                    status: SignatureStatus::NotASigner,
+                    role: SignerRole::Viewer,
+                    acted_by: ActedBy::Signer,
+                    timestamp: 0,
+                    rejection_reason: RejectionReason::Unspecified,
+                }
+            ],
+            progress: SigningProgress {
+                threshold: 2,
+                signed: 1,
+                signers: 2,
+            },
+        }
+    );
+    assert_eq!(
+        documents.list_documents(&0, &1).documents,
+        vec![&e, view.clone()]
+    );
+
+    let reason = BytesN::from_array(&e, &[9; 32]);
+    documents.sign_document(
+        &hash,
+        &signer2,
+        &SignatureStatus::Rejected,
+        &2,
+        &None,
+        &Some(reason.clone()),
+    );
+    let views = documents.get_document_views(&vec![&e, 2, 1]);
+    assert_eq!(views.len(), 2);
+    assert_eq!(views.get_unchecked(0).token_id, 2);
+    assert_eq!(views.get_unchecked(0).state, DocumentState::Rejected);
+    assert_eq!(views.get_unchecked(0).progress.signers, 3);
+    assert_eq!(
+        views
+            .get_unchecked(0)
+            .signers
+            .get_unchecked(1)
+            .rejection_reason,
+        RejectionReason::Hash(reason)
+    );
+    assert_eq!(views.get_unchecked(1), view);
+}
@@ -0,0 +1,96 @@
+use soroban_sdk::Env;
+
+use crate::address_index::{read_owner_document_index, read_signer_document_index};
+use crate::document::{read_document, read_signers, read_token_index, read_version};
+use crate::storage_types::{
+    AddressIndexKey, DataKey, SignatureDataKey, VersionDataKey,
+    DOCUMENT_BUMP_AMOUNT_HIGH_WATERMARK, DOCUMENT_BUMP_AMOUNT_LOW_WATERMARK,
+    INSTANCE_BUMP_AMOUNT_HIGH_WATERMARK, INSTANCE_BUMP_AMOUNT_LOW_WATERMARK,
+};
+
+pub fn bump_instance(e: &Env) {
+    e.storage().instance().bump(
+        INSTANCE_BUMP_AMOUNT_LOW_WATERMARK,
+        INSTANCE_BUMP_AMOUNT_HIGH_WATERMARK,
+    );
+}
+
+/// Bumps a persistent entry if it exists.
+pub fn bump_persistent(e: &Env, key: &DataKey) {
+    if e.storage().persistent().has(key) {
+        e.storage().persistent().bump(
+            key,
+            DOCUMENT_BUMP_AMOUNT_LOW_WATERMARK,
+            DOCUMENT_BUMP_AMOUNT_HIGH_WATERMARK,
+        );
+    }
+}
+
+/// Bumps the contract instance and the entries read when a document is
+/// signed or viewed: its record, signer list and each signer's signature,
+/// receipt and rejection. `bump_document_entries` reaches the rest.
+pub fn bump_document(e: &Env, token_id: u32) {
+    bump_instance(e);
+    bump_persistent(e, &DataKey::Document(token_id));
+    bump_persistent(e, &DataKey::Signers(token_id));
+    for signer in read_signers(e, token_id).iter() {
+        let key = SignatureDataKey { token_id, signer };
+        bump_persistent(e, &DataKey::Signature(key.clone()));
+        bump_persistent(e, &DataKey::Receipt(key.clone()));
+        bump_persistent(e, &DataKey::Rejection(key));
+    }
+}
+
+/// Bumps every entry belonging to a document: its record, participants and
+/// their nonces and document lists, receipts, revocations, rejections, audit
+/// trail, archived versions and index entries, along with the contract
+/// instance. The number of entries grows with the signers and versions, so
+/// this only runs on request.
+pub fn bump_document_entries(e: &Env, token_id: u32) {
+    bump_instance(e);
+
+    let document = match read_document(e, token_id) {
+        Some(document) => document,
+        None => return,
+    };
+    bump_document(e, token_id);
+    bump_persistent(e, &DataKey::Replacements(token_id));
+    bump_persistent(e, &DataKey::Closure(token_id));
+    bump_persistent(e, &DataKey::TokenCount);
+    bump_persistent(e, &DataKey::TokenIndex(token_id));
+    if let Some(index) = read_token_index(e, token_id) {
+        bump_persistent(e, &DataKey::TokenByIndex(index));
+    }
+    bump_persistent(e, &DataKey::TokensByHash(document.document_hash));
+    bump_persistent(e, &DataKey::OwnerDocumentCount(document.owner.clone()));
+    bump_persistent(e, &DataKey::OwnerDocumentIndex(token_id));
+    if let Some(index) = read_owner_document_index(e, token_id) {
+        let key = AddressIndexKey {
+            address: document.owner,
+            index,
+        };
+        bump_persistent(e, &DataKey::OwnerDocument(key));
+    }
+
+    for signer in read_signers(e, token_id).iter() {
+        bump_persistent(e, &DataKey::Nonce(signer.clone()));
+        bump_persistent(e, &DataKey::SignerDocumentCount(signer.clone()));
+        if let Some(index) = read_signer_document_index(e, signer.clone(), token_id) {
+            let key = AddressIndexKey {
+                address: signer.clone(),
+                index,
+            };
+            bump_persistent(e, &DataKey::SignerDocument(key));
+        }
+        let key = SignatureDataKey { token_id, signer };
+        bump_persistent(e, &DataKey::SignerDocumentIndex(key.clone()));
+        bump_persistent(e, &DataKey::Revocations(key));
+    }
+
+    for version in 1..document.version {
+        bump_persistent(e, &DataKey::Version(VersionDataKey { token_id, version }));
+        if let Some(archived) = read_version(e, token_id, version) {
+            bump_persistent(e, &DataKey::TokensByHash(archived.document_hash));
+        }
+    }
+}